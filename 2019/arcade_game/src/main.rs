@@ -1,12 +1,12 @@
-use intcode::program::{Int, Program};
-use intcode::{get_data_from_path, get_data_from_str};
+use intcode::file::ProgramFile;
+use intcode::program::Program;
 use std::collections::HashMap;
 use std::sync::mpsc::channel;
 use std::thread;
 
 fn main() {
-    let mut data = get_data_from_path("data/input.txt");
-    data[0] = 2;
+    let file = ProgramFile::from_path("data/input.txt").unwrap_or_else(|e| panic!("{}", e));
+    let data = file.patched("free_play").expect("no free_play patch");
     let (output_sender, output_receiver) = channel();
     let (input_sender, input_receiver) = channel();
 
@@ -29,10 +29,8 @@ fn main() {
                 if out == 3 {
                     x_paddle = x;
                 }
-                if out == 4 {
-                    if input_sender.send((x - x_paddle).signum()).is_err() {
-                        println!("game ended");
-                    };
+                if out == 4 && input_sender.send((x - x_paddle).signum()).is_err() {
+                    println!("game ended");
                 }
                 tiles.insert((x, y), out);
                 // println!(
@@ -1,17 +1,16 @@
+use intcode::file::ProgramFile;
 use intcode::get_data_from_path;
 use intcode::program::{Int, Program};
 use std::collections::HashSet;
-use std::sync::mpsc::channel;
-use std::thread;
 
 fn main() {
-    let mut wtf = b"A,A,B,C,A,C,A,B,C,B\nR,12,L,8,R,6\nR,12,L,6,R,6,R,8,R,6\nL,8,R,8,R,6,R,12\nn\n";
+    let wtf = b"A,A,B,C,A,C,A,B,C,B\nR,12,L,8,R,6\nR,12,L,6,R,6,R,8,R,6\nL,8,R,8,R,6,R,12\nn\n";
     // let mut wtf = b"A,B,A,C,A,B,C,B,C,B\nL,10,R,8,L,6,R,6\nL,8,L,8,R,8\nR,8,L,6,L,10,L,10\nn\n";
     let mut input = wtf.iter().rev().map(|&c| c as Int).collect();
     let mut output = vec![];
-    let mut data = get_data_from_path("data/input.txt");
-    // let mut data = get_data_from_path("data/day_17");
-    data[0] = 2;
+    let file = ProgramFile::from_path("data/input.txt").unwrap_or_else(|e| panic!("{}", e));
+    // let file = ProgramFile::from_path("data/day_17").unwrap_or_else(|e| panic!("{}", e));
+    let data = file.patched("wake_up").expect("no wake_up patch");
     let mut prog = Program::new(&data, &mut input, &mut output);
     prog.run();
     println!(
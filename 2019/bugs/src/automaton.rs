@@ -0,0 +1,381 @@
+use std::collections::{BTreeMap, HashSet};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// a single grid, cells outside of it are always empty
+    Flat,
+    /// a single grid whose borders wrap around
+    Toroidal,
+    /// every grid has a smaller one in its centre tile and sits inside the
+    /// centre tile of a bigger one
+    Recursive,
+}
+
+/// Birth and survival conditions, indexed by the number of infested neighbours
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    birth: u32,
+    survival: u32,
+}
+
+impl Rule {
+    /// None if a count doesn't fit in the rule, which holds counts up to 31
+    pub fn new(birth: &[usize], survival: &[usize]) -> Option<Rule> {
+        let mask = |counts: &[usize]| {
+            counts.iter().try_fold(0u32, |m, &n| {
+                Some(m | 1u32.checked_shl(u32::try_from(n).ok()?)?)
+            })
+        };
+        Some(Rule {
+            birth: mask(birth)?,
+            survival: mask(survival)?,
+        })
+    }
+
+    /// The rule followed by the bugs of Eris: a bug dies unless there is
+    /// exactly one bug adjacent to it and an empty space becomes infested if
+    /// exactly one or two bugs are adjacent to it.
+    pub fn bugs() -> Rule {
+        Rule::new(&[1, 2], &[1]).unwrap()
+    }
+
+    pub fn next(&self, infested: bool, n_bugs: usize) -> bool {
+        let mask = if infested { self.survival } else { self.birth };
+        n_bugs < 32 && mask & (1 << n_bugs) != 0
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BadRule(String);
+
+impl fmt::Display for BadRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "bad rule {:?}, expected something like B12/S1", self.0)
+    }
+}
+
+/// Parses rules in the usual B/S notation, for example `B12/S1`
+impl FromStr for Rule {
+    type Err = BadRule;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = || BadRule(s.to_string());
+        let mut parts = s.trim().split('/');
+        let mut counts = |prefix: char| -> Result<Vec<usize>, BadRule> {
+            let part = parts.next().ok_or_else(bad)?;
+            let digits = part.strip_prefix(prefix).ok_or_else(bad)?;
+            digits
+                .chars()
+                .map(|c| c.to_digit(10).map(|d| d as usize).ok_or_else(bad))
+                .collect()
+        };
+        let birth = counts('B')?;
+        let survival = counts('S')?;
+        if parts.next().is_some() {
+            return Err(bad());
+        }
+        Rule::new(&birth, &survival).ok_or_else(bad)
+    }
+}
+
+/// Neighbour of a cell: the level relative to the cell's own and the index
+/// of the cell in that level
+type Neighbour = (isize, usize);
+
+#[derive(Debug, Clone)]
+pub struct Automaton {
+    width: usize,
+    height: usize,
+    topology: Topology,
+    rule: Rule,
+    neighbours: Vec<Vec<Neighbour>>,
+    /// levels grow inwards: level + 1 lives in the centre tile of level
+    levels: BTreeMap<isize, Vec<bool>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BadLayout {
+    Empty,
+    Ragged {
+        line: usize,
+    },
+    BadTile {
+        line: usize,
+        col: usize,
+        c: char,
+    },
+    /// recursive grids need a centre tile to nest the next level in
+    NoCentre {
+        width: usize,
+        height: usize,
+    },
+}
+
+impl fmt::Display for BadLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BadLayout::Empty => write!(f, "empty layout"),
+            BadLayout::Ragged { line } => write!(f, "line {} has a different width", line),
+            BadLayout::BadTile { line, col, c } => {
+                write!(f, "unexpected tile {:?} at {}:{}", c, line, col)
+            }
+            BadLayout::NoCentre { width, height } => write!(
+                f,
+                "a {}x{} grid has no centre tile to recurse into",
+                width, height
+            ),
+        }
+    }
+}
+
+impl Automaton {
+    pub fn parse(rep: &str, topology: Topology, rule: Rule) -> Result<Automaton, BadLayout> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for (line, l) in rep
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .enumerate()
+        {
+            let w = l.chars().count();
+            if *width.get_or_insert(w) != w {
+                return Err(BadLayout::Ragged { line });
+            }
+            for (col, c) in l.chars().enumerate() {
+                match c {
+                    '#' => cells.push(true),
+                    '.' | '?' => cells.push(false),
+                    c => return Err(BadLayout::BadTile { line, col, c }),
+                }
+            }
+            height += 1;
+        }
+        let width = width.ok_or(BadLayout::Empty)?;
+        if topology == Topology::Recursive
+            && (width % 2 == 0 || height % 2 == 0 || width < 3 || height < 3)
+        {
+            return Err(BadLayout::NoCentre { width, height });
+        }
+
+        let mut automaton = Automaton {
+            width,
+            height,
+            topology,
+            rule,
+            neighbours: vec![],
+            levels: BTreeMap::new(),
+        };
+        if let Some(centre) = automaton.centre() {
+            cells[centre] = false;
+        }
+        automaton.neighbours = (0..cells.len())
+            .map(|i| automaton.compute_neighbours(i))
+            .collect();
+        automaton.levels.insert(0, cells);
+        Ok(automaton)
+    }
+
+    fn centre(&self) -> Option<usize> {
+        match self.topology {
+            Topology::Recursive => Some(self.index(self.width / 2, self.height / 2)),
+            _ => None,
+        }
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        x + self.width * y
+    }
+
+    fn compute_neighbours(&self, i: usize) -> Vec<Neighbour> {
+        let (w, h) = (self.width as isize, self.height as isize);
+        let (x, y) = ((i % self.width) as isize, (i / self.width) as isize);
+        let centre = self.centre();
+        if Some(i) == centre {
+            return vec![];
+        }
+        let (cx, cy) = (w / 2, h / 2);
+
+        let mut neighbours = vec![];
+        for &(dx, dy) in &[(0, -1), (1, 0), (0, 1), (-1, 0)] {
+            let (nx, ny) = (x + dx, y + dy);
+            let inside = 0 <= nx && nx < w && 0 <= ny && ny < h;
+            match self.topology {
+                _ if inside && Some(self.index(nx as usize, ny as usize)) == centre => {
+                    // the edge of the inner level facing this cell
+                    let edge: Vec<_> = match (dx, dy) {
+                        (0, -1) => (0..w).map(|ex| (ex, h - 1)).collect(),
+                        (0, 1) => (0..w).map(|ex| (ex, 0)).collect(),
+                        (-1, 0) => (0..h).map(|ey| (w - 1, ey)).collect(),
+                        _ => (0..h).map(|ey| (0, ey)).collect(),
+                    };
+                    neighbours.extend(
+                        edge.into_iter()
+                            .map(|(ex, ey)| (1, self.index(ex as usize, ey as usize))),
+                    );
+                }
+                _ if inside => neighbours.push((0, self.index(nx as usize, ny as usize))),
+                Topology::Flat => {}
+                Topology::Toroidal => {
+                    let (nx, ny) = (nx.rem_euclid(w), ny.rem_euclid(h));
+                    neighbours.push((0, self.index(nx as usize, ny as usize)));
+                }
+                Topology::Recursive => {
+                    // the tile of the outer level next to its centre
+                    let (ox, oy) = (cx + dx, cy + dy);
+                    neighbours.push((-1, self.index(ox as usize, oy as usize)));
+                }
+            }
+        }
+        neighbours
+    }
+
+    fn is_infested(&self, level: isize, i: usize) -> bool {
+        self.levels.get(&level).is_some_and(|cells| cells[i])
+    }
+
+    pub fn step(&mut self) {
+        let mut levels = self.levels.keys().cloned().collect::<Vec<_>>();
+        if self.topology == Topology::Recursive {
+            // bugs can spread one level further in each direction
+            let (lower, upper) = (levels[0], levels[levels.len() - 1]);
+            levels.insert(0, lower - 1);
+            levels.push(upper + 1);
+        }
+
+        let centre = self.centre();
+        let mut new = BTreeMap::new();
+        for level in levels {
+            let cells = (0..self.width * self.height)
+                .map(|i| {
+                    let n_bugs = self.neighbours[i]
+                        .iter()
+                        .filter(|&&(dl, n)| self.is_infested(level + dl, n))
+                        .count();
+                    Some(i) != centre && self.rule.next(self.is_infested(level, i), n_bugs)
+                })
+                .collect::<Vec<_>>();
+            new.insert(level, cells);
+        }
+        self.levels = new;
+        self.trim();
+    }
+
+    /// drops the empty levels on the outer and inner ends
+    fn trim(&mut self) {
+        while self.levels.len() > 1 {
+            let (&first, cells) = self.levels.iter().next().unwrap();
+            if cells.iter().any(|&c| c) {
+                break;
+            }
+            self.levels.remove(&first);
+        }
+        while self.levels.len() > 1 {
+            let (&last, cells) = self.levels.iter().next_back().unwrap();
+            if cells.iter().any(|&c| c) {
+                break;
+            }
+            self.levels.remove(&last);
+        }
+    }
+
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// Evolves until a layout appears for the second time, returning the
+    /// number of steps it took. Recursive grids keep growing and may never
+    /// repeat, so they are rejected with None.
+    pub fn evolve_until_repeat(&mut self) -> Option<usize> {
+        if self.topology == Topology::Recursive {
+            return None;
+        }
+        let mut seen = HashSet::new();
+        let mut steps = 0;
+        while seen.insert(self.levels.clone()) {
+            self.step();
+            steps += 1;
+        }
+        Some(steps)
+    }
+
+    pub fn bugs(&self) -> usize {
+        self.levels.values().flatten().filter(|&&c| c).count()
+    }
+
+    /// Each infested tile is worth 2 to the power of its index, reading the
+    /// grid left to right and top to bottom. None if an infested tile is
+    /// past the 64th, as its worth doesn't fit.
+    pub fn biodiversity(&self, level: isize) -> Option<u64> {
+        self.levels.get(&level).map_or(Some(0), |cells| {
+            cells
+                .iter()
+                .enumerate()
+                .filter(|(_, &c)| c)
+                .map(|(i, _)| 1u64.checked_shl(u32::try_from(i).ok()?))
+                .sum()
+        })
+    }
+}
+
+impl fmt::Display for Automaton {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let centre = self.centre();
+        for (n, (level, cells)) in self.levels.iter().enumerate() {
+            if self.topology == Topology::Recursive {
+                if n > 0 {
+                    writeln!(f)?;
+                }
+                writeln!(f, "LEVEL {}", level)?;
+            }
+            for row in cells.chunks(self.width).enumerate().map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(x, &infested)| match infested {
+                        _ if Some(self.index(x, y)) == centre => '?',
+                        true => '#',
+                        false => '.',
+                    })
+                    .collect::<String>()
+            }) {
+                writeln!(f, "{}", row)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits() {
+        assert!(Rule::new(&[31], &[]).is_some());
+        assert_eq!(Rule::new(&[32], &[]), None);
+        assert_eq!(Rule::new(&[], &[usize::MAX]), None);
+
+        let row = "#".repeat(9);
+        let big = [row.as_str(); 9].join("\n");
+        let big = Automaton::parse(&big, Topology::Flat, Rule::bugs()).unwrap();
+        assert_eq!(big.biodiversity(0), None);
+        assert_eq!(big.biodiversity(1), Some(0));
+
+        let small = Automaton::parse("#.\n.#", Topology::Flat, Rule::bugs()).unwrap();
+        assert_eq!(small.biodiversity(0), Some(9));
+
+        let mut recursive = Automaton::parse(
+            "....#\n#..#.\n#..##\n..#..\n#....",
+            Topology::Recursive,
+            Rule::bugs(),
+        )
+        .unwrap();
+        assert_eq!(recursive.evolve_until_repeat(), None);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::shuffle_trick::Trick;
+use number_theory::mod_mul;
 
 pub trait Shuffle {
     fn shuffle(&mut self, trick: &Trick);
@@ -90,6 +91,6 @@ impl Shufflable for IndexSim {
     }
 
     fn deal_inc(&mut self, inc: usize) {
-        self.i = ((self.i as u128 * inc as u128) as usize).rem_euclid(self.nc);
+        self.i = mod_mul(self.i as u64, inc as u64, self.nc as u64) as usize;
     }
 }
@@ -1,3 +1,4 @@
+use number_theory::mod_inverse;
 use std::fs::read_to_string;
 pub use Trick::*;
 
@@ -26,29 +27,8 @@ pub fn parse_tricks(path: &str) -> Vec<Trick> {
 }
 
 fn inverse(a: usize, n: usize) -> usize {
-    let a = a as isize;
-    let n = n as isize;
-    let mut t = 0;
-    let mut newt = 1;
-    let mut r = n;
-    let mut newr = a;
-
-    while newr != 0 {
-        let quotient = r.div_euclid(newr);
-        let tmpt = t;
-        let tmpr = r;
-        t = newt;
-        newt = tmpt - quotient * newt;
-        r = newr;
-        newr = tmpr - quotient * newr;
-    }
-    if r > 1 {
-        panic!("not invertible {} mod {}", a, n);
-    }
-    if t < 0 {
-        t = t + n;
-    }
-    t as usize
+    mod_inverse(a as u64, n as u64).unwrap_or_else(|| panic!("not invertible {} mod {}", a, n))
+        as usize
 }
 
 pub fn parse_inverse_tricks(path: &str, nc: usize) -> Vec<Trick> {
@@ -0,0 +1,328 @@
+use crate::program::{Int, Program};
+use std::fmt;
+use std::fs::read_to_string;
+
+/// Writes to memory applied before running the program, like inserting
+/// quarters in the arcade cabinet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patch {
+    pub name: String,
+    pub writes: Vec<(usize, Int)>,
+}
+
+/// A program together with whatever is needed to run it. Besides the comma
+/// separated program, which may span several lines, a file can contain:
+///
+/// ```text
+/// # comments
+/// @patch free_play: 0=2
+/// @patch gravity_assist: 1=12, 2=2
+/// @input 1
+/// @expect 3306701
+/// @expect gravity_assist: 3306701
+/// ```
+///
+/// An `@expect` naming a patch is the output of the program with that patch
+/// applied.
+///
+/// A file with just the program is a valid program file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramFile {
+    pub data: Vec<Int>,
+    pub patches: Vec<Patch>,
+    /// inputs in the order the program reads them
+    pub inputs: Vec<Int>,
+    pub expected: Vec<Expectation>,
+}
+
+/// Outputs of the program, with the named patch applied if any
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expectation {
+    pub patch: Option<String>,
+    pub outputs: Vec<Int>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    BadNumber,
+    UnknownDirective,
+    /// patches look like `name: address=value, ...`
+    BadPatch,
+    /// the patch writes outside of the program
+    PatchOutOfBounds,
+    DuplicatedPatch,
+    /// an expectation for a patch the file does not define
+    UnknownPatch,
+    EmptyProgram,
+    Io(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// 1 based position of the offending token
+    pub line: usize,
+    pub column: usize,
+    pub token: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let what = match &self.kind {
+            ParseErrorKind::BadNumber => "bad number",
+            ParseErrorKind::UnknownDirective => "unknown directive",
+            ParseErrorKind::BadPatch => "bad patch",
+            ParseErrorKind::PatchOutOfBounds => "patch out of the program",
+            ParseErrorKind::DuplicatedPatch => "duplicated patch",
+            ParseErrorKind::UnknownPatch => "unknown patch",
+            ParseErrorKind::EmptyProgram => "empty program",
+            ParseErrorKind::Io(e) => return write!(f, "{}: {}", self.token, e),
+        };
+        write!(
+            f,
+            "{} {:?} at {}:{}",
+            what, self.token, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A piece of a line and where it starts
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Token<'a> {
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            kind,
+            line: self.line,
+            column: self.column,
+            token: self.text.to_string(),
+        }
+    }
+
+    fn trim(self) -> Token<'a> {
+        let start = self.text.len() - self.text.trim_start().len();
+        Token {
+            text: self.text.trim(),
+            column: self.column + start,
+            ..self
+        }
+    }
+
+    fn split(self, pat: char) -> impl Iterator<Item = Token<'a>> {
+        let mut column = self.column;
+        self.text.split(pat).map(move |text| {
+            let token = Token {
+                text,
+                column,
+                ..self
+            }
+            .trim();
+            column += text.len() + 1;
+            token
+        })
+    }
+
+    fn split_once(self, pat: char) -> Option<(Token<'a>, Token<'a>)> {
+        let (a, b) = self.text.split_once(pat)?;
+        let left = Token { text: a, ..self }.trim();
+        let right = Token {
+            text: b,
+            column: self.column + a.len() + 1,
+            ..self
+        }
+        .trim();
+        Some((left, right))
+    }
+
+    fn number<T: std::str::FromStr>(self) -> Result<T, ParseError> {
+        self.text
+            .parse()
+            .map_err(|_| self.error(ParseErrorKind::BadNumber))
+    }
+}
+
+fn numbers(token: Token) -> Result<Vec<Int>, ParseError> {
+    token
+        .split(',')
+        .filter(|t| !t.text.is_empty())
+        .map(Token::number)
+        .collect()
+}
+
+impl ProgramFile {
+    pub fn parse(string: &str) -> Result<ProgramFile, ParseError> {
+        let mut data = vec![];
+        let mut patches: Vec<(Patch, Vec<Token>)> = vec![];
+        let mut inputs = vec![];
+        let mut expected: Vec<(Expectation, Option<Token>)> = vec![];
+
+        for (i, text) in string.lines().enumerate() {
+            let line = Token {
+                text,
+                line: i + 1,
+                column: 1,
+            }
+            .trim();
+            if line.text.is_empty() || line.text.starts_with('#') {
+                continue;
+            }
+            if !line.text.starts_with('@') {
+                let tokens = line.split(',').collect::<Vec<_>>();
+                for (j, token) in tokens.iter().enumerate() {
+                    // programs spanning several lines end them with a comma
+                    if token.text.is_empty() && j > 0 && j == tokens.len() - 1 {
+                        continue;
+                    }
+                    data.push(token.number()?);
+                }
+                continue;
+            }
+
+            let (directive, rest) = match line.text.find(char::is_whitespace) {
+                Some(end) => (
+                    Token {
+                        text: &line.text[..end],
+                        ..line
+                    },
+                    Token {
+                        text: &line.text[end..],
+                        column: line.column + end,
+                        ..line
+                    }
+                    .trim(),
+                ),
+                None => (line, Token { text: "", ..line }),
+            };
+            match directive.text {
+                "@input" => inputs.extend(numbers(rest)?),
+                "@expect" => {
+                    let (patch, outputs) = match rest.split_once(':') {
+                        Some((name, outputs)) => (Some(name), outputs),
+                        None => (None, rest),
+                    };
+                    let outputs = numbers(outputs)?;
+                    let name = patch.map(|p| p.text);
+                    match expected
+                        .iter_mut()
+                        .find(|(e, _)| e.patch.as_deref() == name)
+                    {
+                        Some((e, _)) => e.outputs.extend(outputs),
+                        None => expected.push((
+                            Expectation {
+                                patch: name.map(str::to_string),
+                                outputs,
+                            },
+                            patch,
+                        )),
+                    }
+                }
+                "@patch" => {
+                    let (name, writes) = rest
+                        .split_once(':')
+                        .ok_or_else(|| rest.error(ParseErrorKind::BadPatch))?;
+                    if name.text.is_empty() {
+                        return Err(rest.error(ParseErrorKind::BadPatch));
+                    }
+                    if patches.iter().any(|(p, _)| p.name == name.text) {
+                        return Err(name.error(ParseErrorKind::DuplicatedPatch));
+                    }
+                    let mut patch = Patch {
+                        name: name.text.to_string(),
+                        writes: vec![],
+                    };
+                    let mut addresses = vec![];
+                    for write in writes.split(',') {
+                        let (address, value) = write
+                            .split_once('=')
+                            .ok_or_else(|| write.error(ParseErrorKind::BadPatch))?;
+                        patch.writes.push((address.number()?, value.number()?));
+                        addresses.push(address);
+                    }
+                    patches.push((patch, addresses));
+                }
+                _ => return Err(directive.error(ParseErrorKind::UnknownDirective)),
+            }
+        }
+
+        if data.is_empty() {
+            return Err(ParseError {
+                kind: ParseErrorKind::EmptyProgram,
+                line: string.lines().count(),
+                column: 1,
+                token: String::new(),
+            });
+        }
+        for (patch, addresses) in &patches {
+            for (&(address, _), token) in patch.writes.iter().zip(addresses) {
+                if address >= data.len() {
+                    return Err(token.error(ParseErrorKind::PatchOutOfBounds));
+                }
+            }
+        }
+
+        for (_, patch) in &expected {
+            if let Some(name) = patch {
+                if !patches.iter().any(|(p, _)| p.name == name.text) {
+                    return Err(name.error(ParseErrorKind::UnknownPatch));
+                }
+            }
+        }
+
+        Ok(ProgramFile {
+            data,
+            patches: patches.into_iter().map(|(p, _)| p).collect(),
+            inputs,
+            expected: expected.into_iter().map(|(e, _)| e).collect(),
+        })
+    }
+
+    pub fn from_path(path: &str) -> Result<ProgramFile, ParseError> {
+        let string = read_to_string(path).map_err(|e| ParseError {
+            kind: ParseErrorKind::Io(e.to_string()),
+            line: 0,
+            column: 0,
+            token: path.to_string(),
+        })?;
+        ProgramFile::parse(&string)
+    }
+
+    pub fn patch(&self, name: &str) -> Option<&Patch> {
+        self.patches.iter().find(|p| p.name == name)
+    }
+
+    /// The program with the named patch applied
+    pub fn patched(&self, name: &str) -> Option<Vec<Int>> {
+        let mut data = self.data.clone();
+        for &(address, value) in &self.patch(name)?.writes {
+            data[address] = value;
+        }
+        Some(data)
+    }
+
+    /// Runs `data` feeding it the default inputs and returns its outputs
+    pub fn run(&self, data: &[Int]) -> Vec<Int> {
+        let mut input = self.inputs.iter().rev().cloned().collect::<Vec<_>>();
+        let mut output = vec![];
+        Program::new(data, &mut input, &mut output).run();
+        output
+    }
+
+    /// Runs the program, with the named patch applied if any, feeding it the
+    /// default inputs and compares its outputs with the expected ones. None
+    /// if the file expects nothing for that patch.
+    pub fn check(&self, patch: Option<&str>) -> Option<bool> {
+        let expected = self.expected.iter().find(|e| e.patch.as_deref() == patch)?;
+        let data = match patch {
+            Some(name) => self.patched(name)?,
+            None => self.data.clone(),
+        };
+        Some(self.run(&data) == expected.outputs)
+    }
+}
@@ -1,11 +1,11 @@
+pub mod file;
 mod opcode;
 pub mod program;
 pub mod solution_7a;
 pub mod solution_7b;
 
-use program::{Int, ProgReceiver, ProgSender, Program};
-use std::env;
-use std::fs::read_to_string;
+use file::ProgramFile;
+use program::{Int, ProgReceiver, ProgSender};
 
 impl ProgSender for &mut Vec<Int> {
     fn put(&mut self, num: Int) {
@@ -20,13 +20,15 @@ impl ProgReceiver for &mut Vec<Int> {
 }
 
 pub fn get_data_from_path(path: &str) -> Vec<Int> {
-    get_data_from_str(&read_to_string(path).expect("bad input"))
+    match ProgramFile::from_path(path) {
+        Ok(file) => file.data,
+        Err(e) => panic!("bad input: {}", e),
+    }
 }
 
 pub fn get_data_from_str(string: &str) -> Vec<Int> {
-    string
-        .trim()
-        .split(',')
-        .map(|x| Int::from_str_radix(x, 10).unwrap())
-        .collect()
+    match ProgramFile::parse(string) {
+        Ok(file) => file.data,
+        Err(e) => panic!("bad input: {}", e),
+    }
 }
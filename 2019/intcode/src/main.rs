@@ -2,10 +2,10 @@ use intcode::program::{Int, Program};
 use intcode::{get_data_from_path, solution_7a, solution_7b};
 use std::env;
 
-fn simple_run(data: &Vec<Int>, debug: bool) {
+fn simple_run(data: &[Int], debug: bool) {
     let mut input = vec![];
     let mut output = vec![];
-    let mut program = Program::new(&data, &mut input, &mut output);
+    let mut program = Program::new(data, &mut input, &mut output);
     if debug {
         program.run_debug_mode();
     } else {
@@ -25,8 +25,8 @@ pub trait ProgReceiver: Debug {
 }
 
 impl<S: ProgSender, R: ProgReceiver> Program<S, R> {
-    pub fn new(data: &Vec<Int>, input: R, output: S) -> Self {
-        let mut mem = data.clone();
+    pub fn new(data: &[Int], input: R, output: S) -> Self {
+        let mem = data.to_vec();
         Program {
             mem,
             pointer: 0,
@@ -15,7 +15,7 @@ impl ProgReceiver for Receiver<Int> {
     }
 }
 
-fn try_config(data: &Vec<Int>, phases: Vec<Int>) -> Option<Int> {
+fn try_config(data: &[Int], phases: Vec<Int>) -> Option<Int> {
     let (sender_a, receiver_b) = channel();
     let (sender_b, receiver_c) = channel();
     let (sender_c, receiver_d) = channel();
@@ -1,4 +1,5 @@
 extern crate intcode;
+use intcode::file::{ParseErrorKind, ProgramFile};
 use intcode::program::{Int, Program};
 use intcode::{get_data_from_path, get_data_from_str};
 
@@ -206,3 +207,57 @@ fn test_09a_final() {
     Program::new(&data, &mut input, &mut output).run();
     assert_eq!(&output, &[4080871669]);
 }
+
+#[test]
+fn test_program_file() {
+    let file = ProgramFile::parse(
+        "\
+# day 2 example
+@patch gravity_assist: 1=10, 2=10
+@patch noop: 1=1
+@expect 1
+1,9,10,3,
+2,3,11,0,
+99,30,40,50
+",
+    )
+    .unwrap();
+    assert_eq!(file.data.len(), 12);
+    assert_eq!(&file.data[..3], &[1, 9, 10]);
+    let patched = file.patched("gravity_assist").unwrap();
+    assert_eq!(&patched[..3], &[1, 10, 10]);
+    assert_eq!(&patched[3..], &file.data[3..]);
+    assert_eq!(file.patched("missing"), None);
+    assert_eq!(file.check(None), Some(false));
+    assert_eq!(file.check(Some("gravity_assist")), None);
+
+    let file = ProgramFile::parse("@input 7\n3,0,4,0,99\n@expect 7").unwrap();
+    assert_eq!(file.run(&file.data), vec![7]);
+    assert_eq!(file.check(None), Some(true));
+
+    // multiplies 7 and 10, or 7 and 7 once patched, and outputs the product
+    let file = ProgramFile::parse(
+        "\
+@patch square: 9=7
+@expect 70
+@expect square: 49
+2,8,9,10,4,10,99,0,7,10,0
+",
+    )
+    .unwrap();
+    assert_eq!(file.check(None), Some(true));
+    assert_eq!(file.check(Some("square")), Some(true));
+    assert_eq!(file.check(Some("missing")), None);
+
+    let err = ProgramFile::parse("@expect p: 1\n99\n").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnknownPatch);
+    assert_eq!((err.line, err.column, err.token.as_str()), (1, 9, "p"));
+
+    let err = ProgramFile::parse("@patch p: 0=1\n1,0, x0,99\n").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::BadNumber);
+    assert_eq!((err.line, err.column, err.token.as_str()), (2, 6, "x0"));
+
+    let err = ProgramFile::parse("@patch p: 7=1\n1,0,0,99\n").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::PatchOutOfBounds);
+    assert_eq!((err.line, err.column), (1, 11));
+}
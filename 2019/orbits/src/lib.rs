@@ -0,0 +1 @@
+pub mod orbit_map;
@@ -0,0 +1,414 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OrbitError {
+    /// lines must look like `A)B`
+    BadLine {
+        line: usize,
+        content: String,
+    },
+    /// a body can orbit only one other body
+    TwoParents {
+        body: String,
+        parents: [String; 2],
+    },
+    Cycle {
+        bodies: Vec<String>,
+    },
+    /// bodies that don't orbit anything, there should be just one
+    Orphans {
+        roots: Vec<String>,
+    },
+    Empty,
+    UnknownBody(String),
+    OrbitsNothing(String),
+}
+
+impl fmt::Display for OrbitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrbitError::BadLine { line, content } => {
+                write!(f, "line {}: expected A)B, found {:?}", line + 1, content)
+            }
+            OrbitError::TwoParents { body, parents } => {
+                write!(f, "{} orbits both {} and {}", body, parents[0], parents[1])
+            }
+            OrbitError::Cycle { bodies } => write!(f, "orbit cycle: {}", bodies.join(" -> ")),
+            OrbitError::Orphans { roots } => {
+                write!(f, "more than one body orbits nobody: {}", roots.join(", "))
+            }
+            OrbitError::Empty => write!(f, "no orbits"),
+            OrbitError::UnknownBody(body) => write!(f, "unknown body {}", body),
+            OrbitError::OrbitsNothing(body) => write!(f, "{} orbits nothing", body),
+        }
+    }
+}
+
+impl std::error::Error for OrbitError {}
+
+#[derive(Debug)]
+pub struct OrbitMap {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    parent: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    root: usize,
+    depth: Vec<usize>,
+    subtree_size: Vec<usize>,
+}
+
+impl FromStr for OrbitMap {
+    type Err = OrbitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut names = vec![];
+        let mut ids = HashMap::new();
+        let mut parent = vec![];
+
+        for (line, content) in s.lines().enumerate() {
+            let content = content.trim();
+            if content.is_empty() {
+                continue;
+            }
+            let (orbited, orbiting) = match content.split_once(')') {
+                Some((a, b)) if !a.is_empty() && !b.is_empty() && !b.contains(')') => (a, b),
+                _ => {
+                    return Err(OrbitError::BadLine {
+                        line,
+                        content: content.to_string(),
+                    })
+                }
+            };
+            let p = intern(orbited, &mut names, &mut ids, &mut parent);
+            let c = intern(orbiting, &mut names, &mut ids, &mut parent);
+            match parent[c] {
+                Some(other) if other != p => {
+                    return Err(OrbitError::TwoParents {
+                        body: orbiting.to_string(),
+                        parents: [names[other].clone(), orbited.to_string()],
+                    })
+                }
+                _ => parent[c] = Some(p),
+            }
+        }
+
+        OrbitMap::build(names, ids, parent)
+    }
+}
+
+impl OrbitMap {
+    fn build(
+        names: Vec<String>,
+        ids: HashMap<String, usize>,
+        parent: Vec<Option<usize>>,
+    ) -> Result<OrbitMap, OrbitError> {
+        let roots = (0..names.len())
+            .filter(|&b| parent[b].is_none())
+            .collect::<Vec<_>>();
+        let mut children = vec![vec![]; names.len()];
+        for (b, p) in parent.iter().enumerate() {
+            if let Some(p) = p {
+                children[*p].push(b);
+            }
+        }
+
+        let root = match roots[..] {
+            [root] => root,
+            [] if names.is_empty() => return Err(OrbitError::Empty),
+            // every body orbits something, so we are going in circles
+            [] => {
+                return Err(OrbitError::Cycle {
+                    bodies: cycle_from(0, &parent, &names),
+                })
+            }
+            _ => {
+                return Err(OrbitError::Orphans {
+                    roots: roots.iter().map(|&r| names[r].clone()).collect(),
+                })
+            }
+        };
+
+        // bodies in breadth first order, so parents come before children
+        let mut order = vec![root];
+        let mut depth = vec![0; names.len()];
+        let mut i = 0;
+        while i < order.len() {
+            let b = order[i];
+            for &c in &children[b] {
+                depth[c] = depth[b] + 1;
+                order.push(c);
+            }
+            i += 1;
+        }
+        if order.len() != names.len() {
+            let mut reached = vec![false; names.len()];
+            for &b in &order {
+                reached[b] = true;
+            }
+            let lost = (0..names.len()).find(|&b| !reached[b]).unwrap();
+            return Err(OrbitError::Cycle {
+                bodies: cycle_from(lost, &parent, &names),
+            });
+        }
+
+        let mut subtree_size = vec![1; names.len()];
+        for &b in order.iter().rev() {
+            if let Some(p) = parent[b] {
+                subtree_size[p] += subtree_size[b];
+            }
+        }
+
+        Ok(OrbitMap {
+            names,
+            ids,
+            parent,
+            children,
+            root,
+            depth,
+            subtree_size,
+        })
+    }
+
+    fn id(&self, body: &str) -> Result<usize, OrbitError> {
+        self.ids
+            .get(body)
+            .cloned()
+            .ok_or_else(|| OrbitError::UnknownBody(body.to_string()))
+    }
+
+    pub fn root(&self) -> &str {
+        &self.names[self.root]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// The body directly orbited by `body`, if any
+    pub fn parent(&self, body: &str) -> Result<Option<&str>, OrbitError> {
+        Ok(self.parent[self.id(body)?].map(|p| self.names[p].as_str()))
+    }
+
+    /// Number of direct and indirect orbits of `body`
+    pub fn depth(&self, body: &str) -> Result<usize, OrbitError> {
+        Ok(self.depth[self.id(body)?])
+    }
+
+    /// Number of bodies orbiting `body` directly or indirectly, plus itself
+    pub fn subtree_size(&self, body: &str) -> Result<usize, OrbitError> {
+        Ok(self.subtree_size[self.id(body)?])
+    }
+
+    pub fn total_orbits(&self) -> usize {
+        self.depth.iter().sum()
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.depth.iter().cloned().max().unwrap_or_default()
+    }
+
+    /// Bodies nobody orbits
+    pub fn leaves(&self) -> impl Iterator<Item = &str> {
+        (0..self.len())
+            .filter(move |&b| self.children[b].is_empty())
+            .map(move |b| self.names[b].as_str())
+    }
+
+    fn lca_id(&self, mut a: usize, mut b: usize) -> usize {
+        while self.depth[a] > self.depth[b] {
+            a = self.parent[a].unwrap();
+        }
+        while self.depth[b] > self.depth[a] {
+            b = self.parent[b].unwrap();
+        }
+        while a != b {
+            a = self.parent[a].unwrap();
+            b = self.parent[b].unwrap();
+        }
+        a
+    }
+
+    /// The deepest body orbited, directly or indirectly, by both `a` and `b`.
+    /// A body counts as orbiting itself.
+    pub fn lowest_common_ancestor(&self, a: &str, b: &str) -> Result<&str, OrbitError> {
+        let lca = self.lca_id(self.id(a)?, self.id(b)?);
+        Ok(&self.names[lca])
+    }
+
+    /// Number of orbits between the bodies `a` and `b`
+    pub fn distance(&self, a: &str, b: &str) -> Result<usize, OrbitError> {
+        let (a, b) = (self.id(a)?, self.id(b)?);
+        let lca = self.lca_id(a, b);
+        Ok(self.depth[a] + self.depth[b] - 2 * self.depth[lca])
+    }
+
+    /// Orbital transfers needed to move from the body `a` orbits to the body
+    /// `b` orbits
+    pub fn transfers(&self, a: &str, b: &str) -> Result<usize, OrbitError> {
+        let orbited = |body| {
+            self.parent(body)?
+                .ok_or_else(|| OrbitError::OrbitsNothing(body.to_string()))
+        };
+        self.distance(orbited(a)?, orbited(b)?)
+    }
+
+    /// Graphviz representation, with edges from orbited to orbiting bodies
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph orbits {\n");
+        for (b, p) in self.parent.iter().enumerate() {
+            if let Some(p) = p {
+                dot.push_str(&format!(
+                    "    {:?} -> {:?};\n",
+                    self.names[*p], self.names[b]
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn intern(
+    name: &str,
+    names: &mut Vec<String>,
+    ids: &mut HashMap<String, usize>,
+    parent: &mut Vec<Option<usize>>,
+) -> usize {
+    *ids.entry(name.to_string()).or_insert_with(|| {
+        names.push(name.to_string());
+        parent.push(None);
+        names.len() - 1
+    })
+}
+
+/// follows the orbits from `body` until one repeats and returns the loop
+fn cycle_from(mut body: usize, parent: &[Option<usize>], names: &[String]) -> Vec<String> {
+    let mut seen = vec![None; parent.len()];
+    let mut path = vec![];
+    while seen[body].is_none() {
+        seen[body] = Some(path.len());
+        path.push(body);
+        body = parent[body].expect("bodies off the cycle reach the root");
+    }
+    let start = seen[body].unwrap();
+    let mut cycle = path[start..]
+        .iter()
+        .map(|&b| names[b].clone())
+        .collect::<Vec<_>>();
+    cycle.push(names[body].clone());
+    cycle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example(path: &str) -> OrbitMap {
+        std::fs::read_to_string(path).unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn test_examples() {
+        let orbits = example("data/test0.txt");
+        assert_eq!(orbits.total_orbits(), 42);
+        let orbits = example("data/test2.txt");
+        assert_eq!(orbits.transfers("YOU", "SAN"), Ok(4));
+    }
+
+    #[test]
+    fn test_ancestors() {
+        let orbits = example("data/test0.txt");
+        assert_eq!(orbits.lowest_common_ancestor("L", "H"), Ok("B"));
+        assert_eq!(orbits.lowest_common_ancestor("K", "F"), Ok("E"));
+        assert_eq!(orbits.lowest_common_ancestor("D", "L"), Ok("D"));
+        assert_eq!(orbits.lowest_common_ancestor("D", "D"), Ok("D"));
+        assert_eq!(orbits.distance("L", "H"), Ok(8));
+        assert_eq!(orbits.distance("COM", "L"), Ok(7));
+        assert_eq!(orbits.distance("I", "I"), Ok(0));
+    }
+
+    #[test]
+    fn test_stats() {
+        let orbits = example("data/test0.txt");
+        assert_eq!(orbits.root(), "COM");
+        assert_eq!(orbits.len(), 12);
+        assert_eq!(orbits.max_depth(), 7);
+        assert_eq!(orbits.depth("D"), Ok(3));
+        assert_eq!(orbits.subtree_size("E"), Ok(5));
+        assert_eq!(orbits.subtree_size("COM"), Ok(12));
+        assert_eq!(orbits.parent("G"), Ok(Some("B")));
+        assert_eq!(orbits.parent("COM"), Ok(None));
+        let mut leaves: Vec<_> = orbits.leaves().collect();
+        leaves.sort_unstable();
+        assert_eq!(leaves, vec!["F", "H", "I", "L"]);
+    }
+
+    #[test]
+    fn test_dot() {
+        let dot = "COM)B\nB)C\nB)D".parse::<OrbitMap>().unwrap().to_dot();
+        assert_eq!(
+            dot,
+            "digraph orbits {\n    \"COM\" -> \"B\";\n    \"B\" -> \"C\";\n    \"B\" -> \"D\";\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let parse = |s: &str| s.parse::<OrbitMap>().unwrap_err();
+        assert_eq!(
+            parse("COM)B\nB-C"),
+            OrbitError::BadLine {
+                line: 1,
+                content: "B-C".to_string()
+            }
+        );
+        assert_eq!(
+            parse("COM)B\nB-C").to_string(),
+            "line 2: expected A)B, found \"B-C\""
+        );
+        assert_eq!(
+            parse("A)B\nC)B"),
+            OrbitError::TwoParents {
+                body: "B".to_string(),
+                parents: ["A".to_string(), "C".to_string()]
+            }
+        );
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            parse("A)B\nB)A"),
+            OrbitError::Cycle {
+                bodies: names(&["A", "B", "A"])
+            }
+        );
+        // a loop cut off from the root
+        assert_eq!(
+            parse("COM)A\nB)C\nC)B"),
+            OrbitError::Cycle {
+                bodies: names(&["B", "C", "B"])
+            }
+        );
+        assert_eq!(
+            parse("A)B\nC)D"),
+            OrbitError::Orphans {
+                roots: names(&["A", "C"])
+            }
+        );
+        assert_eq!(parse("\n"), OrbitError::Empty);
+
+        let orbits = example("data/test2.txt");
+        assert_eq!(
+            orbits.depth("X"),
+            Err(OrbitError::UnknownBody("X".to_string()))
+        );
+        assert_eq!(
+            orbits.transfers("COM", "SAN"),
+            Err(OrbitError::OrbitsNothing("COM".to_string()))
+        );
+    }
+}
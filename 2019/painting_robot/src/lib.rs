@@ -1,11 +1,17 @@
-use intcode::get_data_from_path;
 use intcode::program::{Int, Program};
-use std::collections::HashMap;
-use std::sync::mpsc::channel;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::mpsc::{channel, Receiver, Sender};
 use std::thread;
 use Direction::*;
 
-enum Direction {
+pub type Colour = usize;
+pub type Position = (Int, Int);
+
+pub const BLACK: Colour = 0;
+pub const WHITE: Colour = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
     Up,
     Left,
     Right,
@@ -32,48 +38,248 @@ impl Direction {
     }
 }
 
-pub fn doshit(start: Int) -> HashMap<(Int, Int), Int> {
-    let (input_sender, input_receiver) = channel();
-    let (output_sender, output_receiver) = channel();
-    let mut painted_cells = HashMap::new();
-    let data = get_data_from_path("data/final.txt");
-    let (mut x, mut y) = (0, 0);
-    let mut direction = Direction::Up;
-    let mut i = 0;
-
-    let prog_thread = thread::spawn(move || {
-        let mut prog = Program::new(&data, input_receiver, output_sender);
-        prog.run();
-    });
-    input_sender.send(start).unwrap();
-
-    while let Ok(out) = output_receiver.recv() {
-        if i % 2 == 0 {
-            // first output: paint
-            painted_cells.insert((x, y), out);
-            i += 1;
-            continue;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+/// Decides what the robot does next
+pub trait Brain {
+    /// Given the colour of the panel under the robot returns the colour to
+    /// paint it and where to turn afterwards, or None when it is done
+    fn think(&mut self, colour: Colour) -> Option<(Colour, Turn)>;
+}
+
+/// Runs an Intcode program in its own thread. The program should be left to
+/// run until it halts: if the brain is dropped before that, the program will
+/// find its input closed.
+pub struct IntcodeBrain {
+    input: Sender<Int>,
+    output: Receiver<Int>,
+    program: Option<thread::JoinHandle<()>>,
+}
+
+impl IntcodeBrain {
+    pub fn new(data: &[Int]) -> IntcodeBrain {
+        let (input_sender, input_receiver) = channel();
+        let (output_sender, output_receiver) = channel();
+        let data = data.to_vec();
+        let program = thread::spawn(move || {
+            let mut prog = Program::new(&data, input_receiver, output_sender);
+            prog.run();
+        });
+        IntcodeBrain {
+            input: input_sender,
+            output: output_receiver,
+            program: Some(program),
+        }
+    }
+}
+
+impl Brain for IntcodeBrain {
+    fn think(&mut self, colour: Colour) -> Option<(Colour, Turn)> {
+        // a halted program has dropped its input and closed its output
+        let _ = self.input.send(colour as Int);
+        let outputs = self
+            .output
+            .recv()
+            .and_then(|paint| Ok((paint, self.output.recv()?)));
+        let (paint, turn) = match outputs {
+            Ok(outputs) => outputs,
+            Err(_) => {
+                if let Some(program) = self.program.take() {
+                    program.join().unwrap();
+                }
+                return None;
+            }
+        };
+        let turn = match turn {
+            0 => Turn::Left,
+            _ => Turn::Right,
+        };
+        Some((paint as Colour, turn))
+    }
+}
+
+/// Generalized Langton's ant: on a panel of colour `c` it turns as
+/// `turns[c]` says and paints the panel with the next colour
+pub struct LangtonsAnt {
+    turns: Vec<Turn>,
+}
+
+impl LangtonsAnt {
+    /// Rules written as a string of `L` and `R`, the classic ant being `RL`
+    pub fn new(rules: &str) -> Option<LangtonsAnt> {
+        let turns = rules
+            .chars()
+            .map(|c| match c {
+                'L' => Some(Turn::Left),
+                'R' => Some(Turn::Right),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if turns.is_empty() {
+            return None;
+        }
+        Some(LangtonsAnt { turns })
+    }
+}
+
+impl Brain for LangtonsAnt {
+    fn think(&mut self, colour: Colour) -> Option<(Colour, Turn)> {
+        let colour = colour % self.turns.len();
+        Some(((colour + 1) % self.turns.len(), self.turns[colour]))
+    }
+}
+
+/// Follows a fixed list of instructions ignoring the hull
+pub struct Scripted {
+    steps: VecDeque<(Colour, Turn)>,
+}
+
+impl Scripted {
+    pub fn new(steps: impl IntoIterator<Item = (Colour, Turn)>) -> Scripted {
+        Scripted {
+            steps: steps.into_iter().collect(),
         }
-        if out == 0 {
-            direction.left(); // second output and it is a 0 (turn left)
-        } else {
-            direction.right(); // second output and it is a 1 (turn right)
+    }
+}
+
+impl Brain for Scripted {
+    fn think(&mut self, _colour: Colour) -> Option<(Colour, Turn)> {
+        self.steps.pop_front()
+    }
+}
+
+/// What happened in a single step of the robot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Observation {
+    pub painted: Position,
+    pub colour: Colour,
+    pub turn: Turn,
+    pub position: Position,
+    pub direction: Direction,
+}
+
+pub struct PaintingRobot<B: Brain> {
+    brain: B,
+    position: Position,
+    direction: Direction,
+    hull: HashMap<Position, Colour>,
+    painted: HashSet<Position>,
+    steps: usize,
+}
+
+impl<B: Brain> PaintingRobot<B> {
+    /// The robot starts facing up on a panel of colour `start`, the rest of
+    /// the hull is black
+    pub fn new(brain: B, start: Colour) -> PaintingRobot<B> {
+        let mut hull = HashMap::new();
+        hull.insert((0, 0), start);
+        PaintingRobot {
+            brain,
+            position: (0, 0),
+            direction: Up,
+            hull,
+            painted: HashSet::new(),
+            steps: 0,
         }
-        match direction {
-            Up => y += 1,
-            Left => x -= 1,
-            Down => y -= 1,
-            Right => x += 1,
+    }
+
+    pub fn colour_at(&self, position: &Position) -> Colour {
+        self.hull.get(position).cloned().unwrap_or(BLACK)
+    }
+
+    pub fn step(&mut self) -> Option<Observation> {
+        let painted = self.position;
+        let (colour, turn) = self.brain.think(self.colour_at(&painted))?;
+        self.hull.insert(painted, colour);
+        self.painted.insert(painted);
+        match turn {
+            Turn::Left => self.direction.left(),
+            Turn::Right => self.direction.right(),
         }
-        if input_sender
-            .send(painted_cells.get(&(x, y)).cloned().unwrap_or_default())
-            .is_err()
-        {
-            println!("program halted");
+        let (x, y) = &mut self.position;
+        match self.direction {
+            Up => *y += 1,
+            Left => *x -= 1,
+            Down => *y -= 1,
+            Right => *x += 1,
         }
-        i += 1;
+        self.steps += 1;
+        Some(Observation {
+            painted,
+            colour,
+            turn,
+            position: self.position,
+            direction: self.direction,
+        })
+    }
+
+    /// Steps until the brain is done or `max_steps` are taken, returning the
+    /// number of steps taken
+    pub fn run_for(&mut self, max_steps: usize) -> usize {
+        let start = self.steps;
+        while self.steps - start < max_steps && self.step().is_some() {}
+        self.steps - start
+    }
+
+    /// Steps until the brain is done
+    pub fn run(&mut self) -> usize {
+        self.run_for(usize::MAX)
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
     }
 
-    prog_thread.join().unwrap();
-    painted_cells
+    /// Panels painted at least once
+    pub fn painted(&self) -> &HashSet<Position> {
+        &self.painted
+    }
+
+    pub fn hull(&self) -> &HashMap<Position, Colour> {
+        &self.hull
+    }
+
+    /// Draws the non black panels as `#` and the rest as `.`, with the top row
+    /// first
+    pub fn render(&self) -> String {
+        let lit = self
+            .hull
+            .iter()
+            .filter(|(_, &colour)| colour != BLACK)
+            .map(|(&p, _)| p)
+            .collect::<Vec<_>>();
+        if lit.is_empty() {
+            return String::new();
+        }
+        let (x_max, x_min, y_max, y_min) = lit.iter().fold(
+            (Int::MIN, Int::MAX, Int::MIN, Int::MAX),
+            |(x_max, x_min, y_max, y_min), &(x, y)| {
+                (x_max.max(x), x_min.min(x), y_max.max(y), y_min.min(y))
+            },
+        );
+
+        let mut picture = String::new();
+        for y in (y_min..=y_max).rev() {
+            for x in x_min..=x_max {
+                match self.colour_at(&(x, y)) {
+                    BLACK => picture.push('.'),
+                    _ => picture.push('#'),
+                }
+            }
+            picture.push('\n');
+        }
+        picture
+    }
 }
@@ -0,0 +1,32 @@
+extern crate painting_robot;
+use painting_robot::Turn::{Left, Right};
+use painting_robot::{LangtonsAnt, PaintingRobot, Scripted, BLACK, WHITE};
+
+#[test]
+fn test_11_example() {
+    let brain = Scripted::new(vec![
+        (WHITE, Left),
+        (BLACK, Left),
+        (WHITE, Left),
+        (WHITE, Left),
+        (BLACK, Right),
+        (WHITE, Left),
+        (WHITE, Left),
+    ]);
+    let mut robot = PaintingRobot::new(brain, BLACK);
+    assert_eq!(robot.run(), 7);
+    assert_eq!(robot.painted().len(), 6);
+    assert_eq!(robot.position(), (0, 1));
+    assert_eq!(robot.render(), "..#\n..#\n##.\n");
+}
+
+#[test]
+fn test_langtons_ant() {
+    let mut robot = PaintingRobot::new(LangtonsAnt::new("RL").unwrap(), BLACK);
+    let first = robot.step().unwrap();
+    assert_eq!(first.painted, (0, 0));
+    assert_eq!(first.colour, WHITE);
+    assert_eq!(first.position, (1, 0));
+    assert_eq!(robot.run_for(10), 10);
+    assert_eq!(robot.steps(), 11);
+}
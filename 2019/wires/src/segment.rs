@@ -0,0 +1,335 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+pub type Point = (i64, i64);
+
+pub fn manhattan((x, y): Point) -> u64 {
+    x.unsigned_abs() + y.unsigned_abs()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    pub from: Point,
+    pub to: Point,
+    /// steps the wire took to get to `from`
+    pub delay: u64,
+}
+
+impl Segment {
+    pub fn is_vertical(&self) -> bool {
+        self.from.0 == self.to.0 && self.from.1 != self.to.1
+    }
+
+    pub fn len(&self) -> u64 {
+        manhattan((self.to.0 - self.from.0, self.to.1 - self.from.1))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.from == self.to
+    }
+
+    /// steps the wire took to get to `p`, which must lie on the segment
+    pub fn delay_at(&self, p: Point) -> u64 {
+        self.delay + manhattan((p.0 - self.from.0, p.1 - self.from.1))
+    }
+
+    /// the coordinate shared by every point of the segment
+    fn line(&self) -> i64 {
+        if self.is_vertical() {
+            self.from.0
+        } else {
+            self.from.1
+        }
+    }
+
+    /// lowest and highest value of the coordinate that changes along the
+    /// segment
+    fn span(&self) -> (i64, i64) {
+        let (a, b) = if self.is_vertical() {
+            (self.from.1, self.to.1)
+        } else {
+            (self.from.0, self.to.0)
+        };
+        (a.min(b), a.max(b))
+    }
+
+    fn point_at(&self, t: i64) -> Point {
+        if self.is_vertical() {
+            (self.from.0, t)
+        } else {
+            (t, self.from.1)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BadStep {
+    pub index: usize,
+    pub step: String,
+}
+
+impl fmt::Display for BadStep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "step {} ({:?}) should be a direction (R, L, U, D) followed by a length",
+            self.index, self.step
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wire {
+    pub segments: Vec<Segment>,
+}
+
+impl FromStr for Wire {
+    type Err = BadStep;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segments = vec![];
+        let mut from = (0, 0);
+        let mut delay = 0;
+        for (index, step) in s.trim().split(',').enumerate() {
+            let bad = || BadStep {
+                index,
+                step: step.to_string(),
+            };
+            let mut chars = step.chars();
+            let (dx, dy) = match chars.next() {
+                Some('R') => (1, 0),
+                Some('L') => (-1, 0),
+                Some('U') => (0, 1),
+                Some('D') => (0, -1),
+                _ => return Err(bad()),
+            };
+            let amount: i64 = chars.as_str().parse().map_err(|_| bad())?;
+            if amount < 0 {
+                return Err(bad());
+            }
+            let shift = |start: i64, d: i64| d.checked_mul(amount)?.checked_add(start);
+            let to = match (shift(from.0, dx), shift(from.1, dy)) {
+                (Some(x), Some(y)) => (x, y),
+                _ => return Err(bad()),
+            };
+            segments.push(Segment { from, to, delay });
+            from = to;
+            delay = delay.checked_add(amount as u64).ok_or_else(bad)?;
+        }
+        Ok(Wire { segments })
+    }
+}
+
+/// A point where two different wires meet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crossing {
+    pub wires: (usize, usize),
+    pub point: Point,
+    /// combined steps both wires took to reach the point
+    pub delay: u64,
+}
+
+/// Finds the points where the segments of `a` and `b` meet. When two segments
+/// overlap only the points that can minimize the distance to the origin or
+/// the delay are reported: the ends of the overlap and the points closest to
+/// the origin.
+fn meet(a: &Wire, b: &Wire, mut found: impl FnMut(Point, u64)) {
+    let (a_vert, a_hor): (Vec<_>, Vec<_>) = a.segments.iter().partition(|s| s.is_vertical());
+    let (b_vert, b_hor): (Vec<_>, Vec<_>) = b.segments.iter().partition(|s| s.is_vertical());
+    sweep(&a_hor, &b_vert, &mut found);
+    sweep(&b_hor, &a_vert, &mut found);
+    overlaps(&a_hor, &b_hor, &mut found);
+    overlaps(&a_vert, &b_vert, &mut found);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Event {
+    Insert(usize),
+    Query(usize),
+    Remove(usize),
+}
+
+/// sweeps a vertical line left to right, keeping the horizontal segments it
+/// crosses indexed by their height
+fn sweep(horizontals: &[&Segment], verticals: &[&Segment], found: &mut impl FnMut(Point, u64)) {
+    let mut events = vec![];
+    for (i, h) in horizontals.iter().enumerate() {
+        let (x0, x1) = h.span();
+        events.push((x0, Event::Insert(i)));
+        events.push((x1, Event::Remove(i)));
+    }
+    for (i, v) in verticals.iter().enumerate() {
+        events.push((v.from.0, Event::Query(i)));
+    }
+    events.sort_unstable();
+
+    let mut active: BTreeMap<i64, Vec<usize>> = BTreeMap::new();
+    for (x, event) in events {
+        match event {
+            Event::Insert(i) => active.entry(horizontals[i].from.1).or_default().push(i),
+            Event::Remove(i) => {
+                let y = horizontals[i].from.1;
+                let at_y = active.get_mut(&y).unwrap();
+                at_y.retain(|&j| j != i);
+                if at_y.is_empty() {
+                    active.remove(&y);
+                }
+            }
+            Event::Query(i) => {
+                let v = verticals[i];
+                let (y0, y1) = v.span();
+                for (&y, at_y) in active.range(y0..=y1) {
+                    for &j in at_y {
+                        let p = (x, y);
+                        found(p, horizontals[j].delay_at(p) + v.delay_at(p));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// finds collinear segments sharing part of their line
+fn overlaps(a: &[&Segment], b: &[&Segment], found: &mut impl FnMut(Point, u64)) {
+    let mut lines: HashMap<i64, Vec<&Segment>> = HashMap::new();
+    for s in b {
+        lines.entry(s.line()).or_default().push(s);
+    }
+    for sa in a {
+        for sb in lines.get(&sa.line()).into_iter().flatten() {
+            let ((a0, a1), (b0, b1)) = (sa.span(), sb.span());
+            let (lo, hi) = (a0.max(b0), a1.min(b1));
+            if lo > hi {
+                continue;
+            }
+            let closest = 0.max(lo).min(hi);
+            // the origin doesn't count, so its neighbours are candidates too
+            let around = [closest - 1, closest + 1];
+            let around = around.iter().filter(|&&t| lo <= t && t <= hi);
+            for &t in [lo, closest, hi].iter().chain(around) {
+                let p = sa.point_at(t);
+                found(p, sa.delay_at(p) + sb.delay_at(p));
+            }
+        }
+    }
+}
+
+/// Crossings between every pair of wires, leaving out the origin where they
+/// all start
+pub fn crossings(wires: &[Wire]) -> Vec<Crossing> {
+    let mut all = vec![];
+    for (i, a) in wires.iter().enumerate() {
+        for (j, b) in wires.iter().enumerate().skip(i + 1) {
+            meet(a, b, |point, delay| {
+                if point != (0, 0) {
+                    all.push(Crossing {
+                        wires: (i, j),
+                        point,
+                        delay,
+                    })
+                }
+            });
+        }
+    }
+    all
+}
+
+/// Manhattan distance from the origin to the closest crossing
+pub fn closest_crossing(wires: &[Wire]) -> Option<u64> {
+    crossings(wires).iter().map(|c| manhattan(c.point)).min()
+}
+
+/// Fewest combined steps two wires need to reach a crossing
+pub fn fastest_crossing(wires: &[Wire]) -> Option<u64> {
+    crossings(wires).iter().map(|c| c.delay).min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wires(paths: &[&str]) -> Vec<Wire> {
+        paths.iter().map(|p| p.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_examples() {
+        let examples = [
+            (["R8,U5,L5,D3", "U7,R6,D4,L4"], 6, 30),
+            (
+                [
+                    "R75,D30,R83,U83,L12,D49,R71,U7,L72",
+                    "U62,R66,U55,R34,D71,R55,D58,R83",
+                ],
+                159,
+                610,
+            ),
+            (
+                [
+                    "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51",
+                    "U98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
+                ],
+                135,
+                410,
+            ),
+        ];
+        for (paths, closest, fastest) in &examples {
+            let wires = wires(paths);
+            assert_eq!(closest_crossing(&wires), Some(*closest));
+            assert_eq!(fastest_crossing(&wires), Some(*fastest));
+        }
+    }
+
+    #[test]
+    fn test_overlaps() {
+        // running along the first wire from x = 2 to 7
+        let along = wires(&["R10", "U1,R2,D1,R5"]);
+        assert_eq!(closest_crossing(&along), Some(2));
+        assert_eq!(fastest_crossing(&along), Some(6));
+
+        // sharing the wire from the origin on, then turning back over it
+        let back = wires(&["R10", "R12,L4"]);
+        assert_eq!(closest_crossing(&back), Some(1));
+        assert_eq!(fastest_crossing(&back), Some(2));
+
+        assert_eq!(closest_crossing(&wires(&["R5", "L5"])), None);
+    }
+
+    #[test]
+    fn test_three_wires() {
+        let wires = wires(&["R8,U5,L5,D3", "U7,R6,D4,L4", "U2,R10"]);
+        let found = crossings(&wires);
+        let between = |pair| {
+            let mut points: Vec<_> = found
+                .iter()
+                .filter(|c| c.wires == pair)
+                .map(|c| (c.point, c.delay))
+                .collect();
+            points.sort_unstable();
+            points.dedup();
+            points
+        };
+        assert_eq!(between((0, 1)), vec![((3, 3), 40), ((6, 5), 30)]);
+        assert_eq!(between((0, 2)), vec![((3, 2), 26), ((8, 2), 20)]);
+        assert_eq!(between((1, 2)), vec![((0, 1), 2), ((0, 2), 4)]);
+        assert_eq!(closest_crossing(&wires), Some(1));
+        assert_eq!(fastest_crossing(&wires), Some(2));
+    }
+
+    #[test]
+    fn test_bad_steps() {
+        let max = i64::MAX;
+        assert_eq!(
+            format!("R{},R1", max).parse::<Wire>(),
+            Err(BadStep {
+                index: 1,
+                step: "R1".to_string()
+            })
+        );
+        let steps = format!("U{},D{},D{}", max, max, max);
+        assert_eq!(steps.parse::<Wire>().unwrap_err().index, 2);
+        assert_eq!("R1,X2".parse::<Wire>().unwrap_err().index, 1);
+        assert_eq!("U-3".parse::<Wire>().unwrap_err().index, 0);
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt;
+
+/// Just enough of an unsigned big integer for answers that outgrow a `u64`:
+/// built up from `u64`s by multiplying and adding, then reduced or printed.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BigUint {
+    /// base 2^32, least significant first, no trailing zeros
+    digits: Vec<u32>,
+}
+
+impl BigUint {
+    fn trim(mut self) -> BigUint {
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+        self
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// None if it doesn't fit
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.digits.as_slice() {
+            [] => Some(0),
+            [low] => Some(u64::from(*low)),
+            [low, high] => Some(u64::from(*high) << 32 | u64::from(*low)),
+            _ => None,
+        }
+    }
+
+    pub fn mul_small(&self, n: u64) -> BigUint {
+        let (low, high) = (n & 0xffff_ffff, n >> 32);
+        let mut result = self.mul_digit(low as u32);
+        if high != 0 {
+            let mut shifted = self.mul_digit(high as u32);
+            shifted.digits.insert(0, 0);
+            result = result.add(&shifted);
+        }
+        result
+    }
+
+    fn mul_digit(&self, d: u32) -> BigUint {
+        let mut carry = 0u64;
+        let mut digits: Vec<u32> = self
+            .digits
+            .iter()
+            .map(|&x| {
+                let product = u64::from(x) * u64::from(d) + carry;
+                carry = product >> 32;
+                product as u32
+            })
+            .collect();
+        digits.push(carry as u32);
+        BigUint { digits }.trim()
+    }
+
+    pub fn add(&self, other: &BigUint) -> BigUint {
+        let len = self.digits.len().max(other.digits.len());
+        let mut carry = 0u64;
+        let mut digits = Vec::with_capacity(len + 1);
+        for i in 0..len {
+            let a = self.digits.get(i).copied().unwrap_or(0);
+            let b = other.digits.get(i).copied().unwrap_or(0);
+            let sum = u64::from(a) + u64::from(b) + carry;
+            digits.push(sum as u32);
+            carry = sum >> 32;
+        }
+        digits.push(carry as u32);
+        BigUint { digits }.trim()
+    }
+
+    /// The quotient and remainder of dividing by `n`, which must not be 0
+    pub fn div_rem_small(&self, n: u64) -> (BigUint, u64) {
+        let n = u128::from(n);
+        let mut rem = 0u128;
+        let mut digits = vec![0; self.digits.len()];
+        for (i, &d) in self.digits.iter().enumerate().rev() {
+            let current = rem << 32 | u128::from(d);
+            digits[i] = (current / n) as u32;
+            rem = current % n;
+        }
+        (BigUint { digits }.trim(), rem as u64)
+    }
+
+    pub fn rem_small(&self, n: u64) -> u64 {
+        self.div_rem_small(n).1
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> BigUint {
+        BigUint {
+            digits: vec![n as u32, (n >> 32) as u32],
+        }
+        .trim()
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const CHUNK: u64 = 1_000_000_000;
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        // nine decimal digits at a time, least significant first
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (quotient, chunk) = rest.div_rem_small(CHUNK);
+            chunks.push(chunk);
+            rest = quotient;
+        }
+        let mut chunks = chunks.iter().rev();
+        write!(f, "{}", chunks.next().unwrap())?;
+        for chunk in chunks {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let max = BigUint::from(u64::MAX);
+        assert_eq!(max.to_u64(), Some(u64::MAX));
+        let squared = max.mul_small(u64::MAX);
+        assert_eq!(squared.to_u64(), None);
+        assert_eq!(
+            squared.to_string(),
+            (u128::from(u64::MAX).pow(2)).to_string()
+        );
+        assert_eq!(
+            squared.add(&max).to_string(),
+            (u128::from(u64::MAX).pow(2) + u128::from(u64::MAX)).to_string()
+        );
+        assert_eq!(squared.div_rem_small(u64::MAX), (max.clone(), 0));
+        assert_eq!(
+            squared.rem_small(1_000_000_007),
+            (u128::from(u64::MAX).pow(2) % 1_000_000_007) as u64
+        );
+        // past u128
+        let huge = squared.mul_small(1_000).add(&BigUint::from(7));
+        assert_eq!(
+            huge.to_string(),
+            "340282366920938463426481119284349108225007"
+        );
+        assert_eq!(BigUint::from(0).to_string(), "0");
+        assert_eq!(BigUint::from(1_000_000_000).to_string(), "1000000000");
+    }
+}
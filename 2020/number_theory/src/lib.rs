@@ -0,0 +1,197 @@
+//! Modular arithmetic and big integers shared by the puzzles that need them
+
+pub mod big;
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// `(g, x, y)` such that `a * x + b * y == g`, `g` being the greatest common
+/// divisor of `a` and `b`, never negative
+pub fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (1, 0);
+    let (mut old_t, mut t) = (0, 1);
+    while r != 0 {
+        let quotient = old_r.div_euclid(r);
+        let next_r = old_r - quotient * r;
+        old_r = r;
+        r = next_r;
+        let next_s = old_s - quotient * s;
+        old_s = s;
+        s = next_s;
+        let next_t = old_t - quotient * t;
+        old_t = t;
+        t = next_t;
+    }
+    if old_r < 0 {
+        (-old_r, -old_s, -old_t)
+    } else {
+        (old_r, old_s, old_t)
+    }
+}
+
+pub fn gcd(a: u64, b: u64) -> u64 {
+    ext_gcd(a.into(), b.into()).0 as u64
+}
+
+/// `a * b mod m` without overflowing
+pub fn mod_mul(a: u64, b: u64, m: u64) -> u64 {
+    (u128::from(a) * u128::from(b) % u128::from(m)) as u64
+}
+
+/// `base` to the power of `exp` mod `m`, by repeated squaring
+pub fn mod_pow(base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    let mut base = base % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mod_mul(result, base, m);
+        }
+        base = mod_mul(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// `x` such that `a * x == 1 mod m`, None if `a` and `m` are not coprime
+pub fn mod_inverse(a: u64, m: u64) -> Option<u64> {
+    let (g, x, _) = ext_gcd(a.into(), m.into());
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(m.into()) as u64)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrtError {
+    /// two of the congruences contradict each other
+    NoSolution,
+    /// the least common multiple of the moduli doesn't fit in a `u64`
+    Overflow,
+    ZeroModulus,
+}
+
+impl fmt::Display for CrtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CrtError::NoSolution => write!(f, "the congruences have no common solution"),
+            CrtError::Overflow => write!(f, "the moduli are too big"),
+            CrtError::ZeroModulus => write!(f, "a modulus is 0"),
+        }
+    }
+}
+
+/// Chinese remainder theorem for moduli that need not be coprime: the
+/// smallest `x` with `x == a mod m` for every `(a, m)`, and the least common
+/// multiple of the moduli, which all other solutions differ from `x` by.
+pub fn crt(congruences: &[(i64, u64)]) -> Result<(u64, u64), CrtError> {
+    let mut x: u64 = 0;
+    let mut lcm: u64 = 1;
+    for &(a, m) in congruences {
+        if m == 0 {
+            return Err(CrtError::ZeroModulus);
+        }
+        let a = (i128::from(a)).rem_euclid(i128::from(m)) as u64;
+        // x + lcm * t == a mod m, so lcm * t == a - x mod m
+        let g = gcd(lcm, m);
+        let diff = i128::from(a) - i128::from(x);
+        if diff % i128::from(g) != 0 {
+            return Err(CrtError::NoSolution);
+        }
+        let m_g = m / g;
+        let inverse = mod_inverse((lcm / g) % m_g, m_g).expect("divided by their gcd");
+        let diff = (diff / i128::from(g)).rem_euclid(i128::from(m_g)) as u64;
+        let t = mod_mul(diff, inverse, m_g);
+        let next = lcm.checked_mul(m_g).ok_or(CrtError::Overflow)?;
+        x = ((u128::from(x) + u128::from(lcm) * u128::from(t)) % u128::from(next)) as u64;
+        lcm = next;
+    }
+    Ok((x, lcm))
+}
+
+/// The smallest `x` with `base` to the power of `x` being `target` mod `m`,
+/// by baby-step giant-step in about `sqrt(m)` steps. Past `x` being 0,
+/// `base` must be coprime with `m`, otherwise or if there is no such `x` it
+/// is None.
+pub fn discrete_log(base: u64, target: u64, m: u64) -> Option<u64> {
+    let (base, target) = (base % m, target % m);
+    // any base to the power of 0, coprime or not
+    if target == 1 % m {
+        return Some(0);
+    }
+    let steps = (m as f64).sqrt().ceil() as u64;
+    // base^j for the baby steps, keeping the smallest j
+    let mut baby = HashMap::with_capacity(steps as usize);
+    let mut power = 1;
+    for j in 0..steps {
+        baby.entry(power).or_insert(j);
+        power = mod_mul(power, base, m);
+    }
+    // target * base^(-steps * i) for the giant steps
+    let giant = mod_pow(mod_inverse(base, m)?, steps, m);
+    let mut gamma = target;
+    for i in 0..steps {
+        if let Some(j) = baby.get(&gamma) {
+            return Some(i * steps + j);
+        }
+        gamma = mod_mul(gamma, giant, m);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(ext_gcd(240, 46), (2, -9, 47));
+        assert_eq!(ext_gcd(-4, 6).0, 2);
+        assert_eq!(ext_gcd(0, 0).0, 0);
+        assert_eq!(gcd(17, 5), 1);
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_pow(7, 8, 20201227), 5764801);
+        assert_eq!(mod_pow(3, 0, 1), 0);
+        assert_eq!(mod_pow(u64::MAX - 1, 3, u64::MAX), u64::MAX - 1);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(6, 9), None);
+        let m = 119315717514047;
+        assert_eq!(mod_mul(mod_inverse(71, m).unwrap(), 71, m), 1);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(0, 3), (3, 4), (4, 5)]), Ok((39, 60)));
+        // not coprime
+        assert_eq!(crt(&[(2, 6), (5, 9)]), Ok((14, 18)));
+        assert_eq!(crt(&[(1, 6), (2, 4)]), Err(CrtError::NoSolution));
+        assert_eq!(crt(&[(-1, 7)]), Ok((6, 7)));
+        assert_eq!(crt(&[]), Ok((0, 1)));
+        assert_eq!(crt(&[(1, 0)]), Err(CrtError::ZeroModulus));
+        assert_eq!(
+            crt(&[(0, u64::MAX), (1, u64::MAX - 1)]),
+            Err(CrtError::Overflow)
+        );
+        // the bus example, each bus leaving its offset after x
+        assert_eq!(
+            crt(&[(0, 17), (-2, 13), (-3, 19)]),
+            Ok((3417, 17 * 13 * 19))
+        );
+    }
+
+    #[test]
+    fn test_discrete_log() {
+        assert_eq!(discrete_log(7, 5764801, 20201227), Some(8));
+        assert_eq!(discrete_log(7, 17807724, 20201227), Some(11));
+        assert_eq!(discrete_log(2, 1, 5), Some(0));
+        // powers of 4 mod 7 are 1, 4, 2
+        assert_eq!(discrete_log(4, 3, 7), None);
+        assert_eq!(discrete_log(2, 1, 4), Some(0));
+        // 2 isn't coprime with 4, so 2^2 == 0 mod 4 isn't found
+        assert_eq!(discrete_log(2, 0, 4), None);
+        assert_eq!(discrete_log(3, 0, 1), Some(0));
+    }
+}
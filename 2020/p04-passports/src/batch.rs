@@ -0,0 +1,132 @@
+use crate::schema::{FieldError, Problem, Schema};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The `key:value` entries of a record, the value being None if the entry
+/// has no `:`
+pub type Record = Vec<(String, Option<String>)>;
+
+pub fn parse_record(entries: &str) -> Record {
+    entries
+        .split_ascii_whitespace()
+        .map(|entry| match entry.split_once(':') {
+            Some((key, value)) => (key.to_string(), Some(value.to_string())),
+            None => (entry.to_string(), None),
+        })
+        .collect()
+}
+
+/// Records separated by blank lines
+pub fn parse_batch(input: &str) -> Vec<Record> {
+    input
+        .replace("\r\n", "\n")
+        .split("\n\n")
+        .map(parse_record)
+        .filter(|r| !r.is_empty())
+        .collect()
+}
+
+/// How the records of a batch fared
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub records: usize,
+    /// records with no required field missing, valid or not
+    pub complete: usize,
+    pub valid: usize,
+    /// how many times each key failed for each reason
+    pub failures: BTreeMap<(String, Problem), usize>,
+    /// the errors of each invalid record, by index in the batch
+    pub errors: Vec<(usize, Vec<FieldError>)>,
+}
+
+impl Report {
+    pub fn new(schema: &Schema, batch: &[Record]) -> Report {
+        let mut report = Report {
+            records: batch.len(),
+            ..Report::default()
+        };
+        for (idx, record) in batch.iter().enumerate() {
+            let errors = schema.validate(record);
+            if !errors.iter().any(|e| e.problem == Problem::Missing) {
+                report.complete += 1;
+            }
+            if errors.is_empty() {
+                report.valid += 1;
+                continue;
+            }
+            for e in &errors {
+                *report
+                    .failures
+                    .entry((e.key.clone(), e.problem.clone()))
+                    .or_default() += 1;
+            }
+            report.errors.push((idx, errors));
+        }
+        report
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} records, {} complete, {} valid",
+            self.records, self.complete, self.valid
+        )?;
+        for ((key, problem), count) in &self.failures {
+            writeln!(f, "{:>5} {} {}", count, key, problem)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(path: &str) -> Report {
+        let batch = parse_batch(&std::fs::read_to_string(path).unwrap());
+        Report::new(&Schema::passport(), &batch)
+    }
+
+    #[test]
+    fn test_examples() {
+        let report = report("data/test.txt");
+        assert_eq!((report.records, report.complete), (4, 2));
+        assert_eq!(
+            report.failures.get(&("hgt".to_string(), Problem::Missing)),
+            Some(&1)
+        );
+        let report = self::report("data/test_bad.txt");
+        assert_eq!((report.records, report.valid), (4, 0));
+        assert_eq!(report.errors.len(), 4);
+        let report = self::report("data/test_good.txt");
+        assert_eq!((report.records, report.valid), (4, 4));
+        assert!(report.failures.is_empty());
+    }
+
+    #[test]
+    fn test_summary() {
+        let batch = parse_batch("byr:1900\n\n\n\nbyr:2000 cid:1\r\n\r\nzzz:1");
+        let schema = Schema::new()
+            .with_required(
+                "byr",
+                crate::schema::Check::Number {
+                    min: 1920,
+                    max: 2002,
+                    digits: None,
+                },
+            )
+            .with_optional("cid", crate::schema::Check::Any);
+        let report = Report::new(&schema, &batch);
+        assert_eq!(
+            report.to_string(),
+            "3 records, 2 complete, 1 valid\n    \
+                 1 byr missing\n    \
+                 1 byr not between 1920 and 2002\n    \
+                 1 zzz unknown field\n"
+        );
+        let invalid: Vec<_> = report.errors.iter().map(|(idx, _)| *idx).collect();
+        assert_eq!(invalid, vec![0, 2]);
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// One position of a pattern, either a literal character or a class like
+/// `[0-9a-f]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Atom {
+    Literal(char),
+    /// inclusive character ranges
+    Class(Vec<(char, char)>),
+}
+
+impl Atom {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Atom::Literal(l) => *l == c,
+            Atom::Class(ranges) => ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi),
+        }
+    }
+}
+
+/// A small subset of regular expressions matched against a whole value:
+/// literal characters and classes, each optionally repeated an exact number
+/// of times with `{n}`, like `#[0-9a-f]{6}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    source: String,
+    atoms: Vec<(Atom, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BadPattern(String);
+
+impl fmt::Display for BadPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "bad pattern {:?}", self.0)
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = BadPattern;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = || BadPattern(s.to_string());
+        let mut atoms = Vec::new();
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            let atom = match c {
+                '[' => {
+                    let mut ranges = Vec::new();
+                    loop {
+                        let lo = chars.next().ok_or_else(bad)?;
+                        if lo == ']' {
+                            break;
+                        }
+                        if chars.peek() == Some(&'-') {
+                            chars.next();
+                            let hi = chars.next().filter(|&hi| hi != ']').ok_or_else(bad)?;
+                            ranges.push((lo, hi));
+                        } else {
+                            ranges.push((lo, lo));
+                        }
+                    }
+                    if ranges.is_empty() {
+                        return Err(bad());
+                    }
+                    Atom::Class(ranges)
+                }
+                '\\' => Atom::Literal(chars.next().ok_or_else(bad)?),
+                ']' | '{' | '}' => return Err(bad()),
+                c => Atom::Literal(c),
+            };
+            let mut times = 1;
+            if chars.peek() == Some(&'{') {
+                chars.next();
+                let mut digits = String::new();
+                loop {
+                    match chars.next().ok_or_else(bad)? {
+                        '}' => break,
+                        c => digits.push(c),
+                    }
+                }
+                times = digits.parse().map_err(|_| bad())?;
+            }
+            atoms.push((atom, times));
+        }
+        Ok(Pattern {
+            source: s.to_string(),
+            atoms,
+        })
+    }
+}
+
+impl Pattern {
+    pub fn matches(&self, value: &str) -> bool {
+        let mut chars = value.chars();
+        self.atoms
+            .iter()
+            .all(|(atom, times)| (0..*times).all(|_| chars.next().is_some_and(|c| atom.matches(c))))
+            && chars.next().is_none()
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns() {
+        let colour: Pattern = "#[0-9a-f]{6}".parse().unwrap();
+        assert!(colour.matches("#123abc"));
+        assert!(!colour.matches("#123abz"));
+        assert!(!colour.matches("123abc"));
+        assert!(!colour.matches("#123abcd"));
+        assert!(!colour.matches("#123ab"));
+
+        let plate: Pattern = "[A-Z_]{2}\\-[0-9]".parse().unwrap();
+        assert!(plate.matches("A_-7"));
+        assert!(!plate.matches("Ab-7"));
+
+        assert!("".parse::<Pattern>().unwrap().matches(""));
+        for bad in &["[a-", "[]", "a{x}", "a}", "\\", "#[0-9a-f]{6", "a{"] {
+            assert_eq!(bad.parse::<Pattern>(), Err(BadPattern(bad.to_string())));
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use crate::pattern::Pattern;
+use std::fmt;
+
+/// What a field's value must look like
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    Any,
+    /// A number between `min` and `max`, written with exactly `digits`
+    /// digits if given
+    Number {
+        min: u64,
+        max: u64,
+        digits: Option<usize>,
+    },
+    /// A number followed by one of the units, each with its own range, like
+    /// `183cm`
+    Measure(Vec<Unit>),
+    Pattern(Pattern),
+    OneOf(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unit {
+    pub suffix: String,
+    pub min: u64,
+    pub max: u64,
+}
+
+impl Unit {
+    pub fn new(suffix: &str, min: u64, max: u64) -> Unit {
+        Unit {
+            suffix: suffix.to_string(),
+            min,
+            max,
+        }
+    }
+}
+
+/// Why a field is not valid
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Problem {
+    Missing,
+    /// the key is not in the schema
+    Unknown,
+    Repeated,
+    /// the entry has no `:` between key and value
+    Malformed,
+    NotANumber,
+    Digits {
+        expected: usize,
+    },
+    OutOfRange {
+        min: u64,
+        max: u64,
+    },
+    BadUnit,
+    NoMatch,
+    NotAllowed,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::Missing => write!(f, "missing"),
+            Problem::Unknown => write!(f, "unknown field"),
+            Problem::Repeated => write!(f, "repeated"),
+            Problem::Malformed => write!(f, "not a key:value pair"),
+            Problem::NotANumber => write!(f, "not a number"),
+            Problem::Digits { expected } => write!(f, "not {} digits", expected),
+            Problem::OutOfRange { min, max } => write!(f, "not between {} and {}", min, max),
+            Problem::BadUnit => write!(f, "missing a valid unit"),
+            Problem::NoMatch => write!(f, "not matching the pattern"),
+            Problem::NotAllowed => write!(f, "not one of the allowed values"),
+        }
+    }
+}
+
+/// A field that failed validation, with its value if it has one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub key: String,
+    pub value: Option<String>,
+    pub problem: Problem,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{}:{} is {}", self.key, value, self.problem),
+            None => write!(f, "{} is {}", self.key, self.problem),
+        }
+    }
+}
+
+impl Check {
+    pub fn check(&self, value: &str) -> Result<(), Problem> {
+        let in_range = |n: u64, min: u64, max: u64| {
+            if n < min || n > max {
+                Err(Problem::OutOfRange { min, max })
+            } else {
+                Ok(())
+            }
+        };
+        let number = |digits: &str| -> Result<u64, Problem> {
+            if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+                return Err(Problem::NotANumber);
+            }
+            digits.parse().map_err(|_| Problem::NotANumber)
+        };
+        match self {
+            Check::Any => Ok(()),
+            Check::Number { min, max, digits } => {
+                let n = number(value)?;
+                match digits {
+                    Some(expected) if value.len() != *expected => Err(Problem::Digits {
+                        expected: *expected,
+                    }),
+                    _ => in_range(n, *min, *max),
+                }
+            }
+            Check::Measure(units) => {
+                let (unit, n) = units
+                    .iter()
+                    .find_map(|u| value.strip_suffix(u.suffix.as_str()).map(|n| (u, n)))
+                    .ok_or(Problem::BadUnit)?;
+                in_range(number(n)?, unit.min, unit.max)
+            }
+            Check::Pattern(pattern) => {
+                if pattern.matches(value) {
+                    Ok(())
+                } else {
+                    Err(Problem::NoMatch)
+                }
+            }
+            Check::OneOf(allowed) => {
+                if allowed.iter().any(|a| a == value) {
+                    Ok(())
+                } else {
+                    Err(Problem::NotAllowed)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Field {
+    key: String,
+    required: bool,
+    check: Check,
+}
+
+/// The fields a record may have and what their values must look like
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Schema {
+    fields: Vec<Field>,
+}
+
+impl Schema {
+    pub fn new() -> Schema {
+        Schema::default()
+    }
+
+    pub fn with_required(mut self, key: &str, check: Check) -> Schema {
+        self.fields.push(Field {
+            key: key.to_string(),
+            required: true,
+            check,
+        });
+        self
+    }
+
+    pub fn with_optional(mut self, key: &str, check: Check) -> Schema {
+        self.fields.push(Field {
+            key: key.to_string(),
+            required: false,
+            check,
+        });
+        self
+    }
+
+    /// Every field of a passport is required except the country id
+    pub fn passport() -> Schema {
+        let year = |min, max| Check::Number {
+            min,
+            max,
+            digits: Some(4),
+        };
+        let eye_colours = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+        Schema::new()
+            .with_required("byr", year(1920, 2002))
+            .with_required("iyr", year(2010, 2020))
+            .with_required("eyr", year(2020, 2030))
+            .with_required(
+                "hgt",
+                Check::Measure(vec![Unit::new("cm", 150, 193), Unit::new("in", 59, 76)]),
+            )
+            .with_required("hcl", Check::Pattern("#[0-9a-f]{6}".parse().unwrap()))
+            .with_required(
+                "ecl",
+                Check::OneOf(eye_colours.iter().map(|c| c.to_string()).collect()),
+            )
+            .with_required(
+                "pid",
+                Check::Number {
+                    min: 0,
+                    max: 999_999_999,
+                    digits: Some(9),
+                },
+            )
+            .with_optional("cid", Check::Any)
+    }
+
+    /// Every failing field of the record, in the order of the schema and then
+    /// of the record. Empty if the record is valid.
+    pub fn validate(&self, record: &[(String, Option<String>)]) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        for field in &self.fields {
+            let mut values = record
+                .iter()
+                .filter(|(key, _)| *key == field.key)
+                .map(|(_, value)| value);
+            match values.next() {
+                // a bare key is no more there than a missing one
+                None | Some(None) if field.required => errors.push(FieldError {
+                    key: field.key.clone(),
+                    value: None,
+                    problem: Problem::Missing,
+                }),
+                None => (),
+                Some(None) => errors.push(FieldError {
+                    key: field.key.clone(),
+                    value: None,
+                    problem: Problem::Malformed,
+                }),
+                Some(Some(value)) => {
+                    if let Err(problem) = field.check.check(value) {
+                        errors.push(FieldError {
+                            key: field.key.clone(),
+                            value: Some(value.clone()),
+                            problem,
+                        });
+                    }
+                }
+            }
+            errors.extend(values.map(|value| FieldError {
+                key: field.key.clone(),
+                value: value.clone(),
+                problem: Problem::Repeated,
+            }));
+        }
+        for (key, value) in record {
+            if self.fields.iter().any(|f| f.key == *key) {
+                continue;
+            }
+            let problem = match value {
+                None => Problem::Malformed,
+                Some(_) => Problem::Unknown,
+            };
+            errors.push(FieldError {
+                key: key.clone(),
+                value: value.clone(),
+                problem,
+            });
+        }
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(entries: &str) -> Vec<(String, Option<String>)> {
+        crate::batch::parse_record(entries)
+    }
+
+    #[test]
+    fn test_checks() {
+        let schema = Schema::passport();
+        let problem = |entries: &str| {
+            let errors = schema.validate(&record(entries));
+            errors.into_iter().next().map(|e| e.problem)
+        };
+        let valid = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 hcl:#623a2f";
+        assert_eq!(problem(valid), None);
+        let with = |entry: &str| format!("{} {}", entry, valid.replacen(&entry[..4], "x:", 1));
+        assert_eq!(
+            problem(&with("byr:2003")),
+            Some(Problem::OutOfRange {
+                min: 1920,
+                max: 2002
+            })
+        );
+        assert_eq!(
+            problem(&with("pid:0123456789")),
+            Some(Problem::Digits { expected: 9 })
+        );
+        assert_eq!(problem(&with("pid:+12345678")), Some(Problem::NotANumber));
+        assert_eq!(problem(&with("hgt:190")), Some(Problem::BadUnit));
+        assert_eq!(
+            problem(&with("hgt:190in")),
+            Some(Problem::OutOfRange { min: 59, max: 76 })
+        );
+        assert_eq!(problem(&with("hgt:cm")), Some(Problem::NotANumber));
+        assert_eq!(problem(&with("hcl:#123abz")), Some(Problem::NoMatch));
+        assert_eq!(problem(&with("ecl:wat")), Some(Problem::NotAllowed));
+    }
+
+    #[test]
+    fn test_every_failure() {
+        let schema = Schema::passport();
+        let errors = schema.validate(&record(
+            "eyr:1972 cid:100 hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926 cid:7 x:1 y",
+        ));
+        let found: Vec<_> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            found,
+            vec![
+                "eyr:1972 is not between 2020 and 2030",
+                "hgt:170 is missing a valid unit",
+                "pid:186cm is not a number",
+                "cid:7 is repeated",
+                "x:1 is unknown field",
+                "y is not a key:value pair",
+            ]
+        );
+        // bare keys count as missing if required
+        let errors = schema.validate(&record(
+            "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 hcl cid",
+        ));
+        let found: Vec<_> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(found, vec!["hcl is missing", "cid is not a key:value pair"]);
+
+        let errors = schema.validate(&record("cid:1"));
+        assert_eq!(errors.len(), 7);
+        assert!(errors.iter().all(|e| e.problem == Problem::Missing));
+    }
+}
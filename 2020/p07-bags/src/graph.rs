@@ -0,0 +1,313 @@
+use crate::parse::Rule;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphError {
+    /// two rules for the same colour
+    Repeated(String),
+    /// bags that end up inside themselves, the first one repeated at the end
+    Cycle(Vec<String>),
+    UnknownColour(String),
+    /// the number of bags doesn't fit in a `u64`
+    Overflow(String),
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GraphError::Repeated(colour) => write!(f, "more than one rule for {} bags", colour),
+            GraphError::Cycle(colours) => {
+                write!(f, "bags inside themselves: {}", colours.join(" -> "))
+            }
+            GraphError::UnknownColour(colour) => write!(f, "unknown colour {}", colour),
+            GraphError::Overflow(colour) => write!(f, "too many bags inside {}", colour),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+/// Which bags go inside which, walkable both ways. Colours that only show up
+/// as contents are bags that contain nothing.
+#[derive(Debug)]
+pub struct BagGraph {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    /// `(count, bag)` for each bag directly inside
+    inside: Vec<Vec<(u64, usize)>>,
+    /// the bags that directly contain each bag
+    outside: Vec<Vec<usize>>,
+    total_inside: RefCell<Vec<Option<u64>>>,
+    containers: RefCell<Vec<Option<Vec<usize>>>>,
+}
+
+impl BagGraph {
+    pub fn new(rules: &[Rule]) -> Result<BagGraph, GraphError> {
+        let mut graph = BagGraph {
+            names: Vec::new(),
+            ids: HashMap::new(),
+            inside: Vec::new(),
+            outside: Vec::new(),
+            total_inside: RefCell::new(Vec::new()),
+            containers: RefCell::new(Vec::new()),
+        };
+        let mut has_rule = Vec::new();
+        for rule in rules {
+            let bag = graph.intern(&rule.colour);
+            has_rule.resize(graph.names.len(), false);
+            if has_rule[bag] {
+                return Err(GraphError::Repeated(rule.colour.clone()));
+            }
+            has_rule[bag] = true;
+            for (count, colour) in &rule.contents {
+                let inner = graph.intern(colour);
+                graph.inside[bag].push((*count, inner));
+                graph.outside[inner].push(bag);
+            }
+        }
+        graph.check_cycles()?;
+        let n = graph.names.len();
+        graph.total_inside = RefCell::new(vec![None; n]);
+        graph.containers = RefCell::new(vec![None; n]);
+        Ok(graph)
+    }
+
+    fn intern(&mut self, colour: &str) -> usize {
+        if let Some(&id) = self.ids.get(colour) {
+            return id;
+        }
+        self.names.push(colour.to_string());
+        self.inside.push(Vec::new());
+        self.outside.push(Vec::new());
+        self.ids.insert(colour.to_string(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    /// Depth first search keeping the path to the bag being visited, so a
+    /// bag found on it closes a cycle
+    fn check_cycles(&self) -> Result<(), GraphError> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State {
+            New,
+            OnPath,
+            Done,
+        }
+        let mut state = vec![State::New; self.names.len()];
+        for start in 0..self.names.len() {
+            if state[start] != State::New {
+                continue;
+            }
+            // each bag on the path with how many of its contents are visited
+            let mut path = vec![(start, 0)];
+            state[start] = State::OnPath;
+            while let Some(&mut (bag, ref mut next)) = path.last_mut() {
+                match self.inside[bag].get(*next) {
+                    Some(&(_, inner)) => {
+                        *next += 1;
+                        match state[inner] {
+                            State::New => {
+                                state[inner] = State::OnPath;
+                                path.push((inner, 0));
+                            }
+                            State::OnPath => {
+                                let from = path.iter().position(|&(b, _)| b == inner).unwrap();
+                                let mut cycle: Vec<_> = path[from..]
+                                    .iter()
+                                    .map(|&(b, _)| self.names[b].clone())
+                                    .collect();
+                                cycle.push(self.names[inner].clone());
+                                return Err(GraphError::Cycle(cycle));
+                            }
+                            State::Done => (),
+                        }
+                    }
+                    None => {
+                        state[bag] = State::Done;
+                        path.pop();
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn id(&self, colour: &str) -> Result<usize, GraphError> {
+        self.ids
+            .get(colour)
+            .cloned()
+            .ok_or_else(|| GraphError::UnknownColour(colour.to_string()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn colours(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(String::as_str)
+    }
+
+    /// The bags directly inside a bag of the colour
+    pub fn contents(&self, colour: &str) -> Result<Vec<(u64, &str)>, GraphError> {
+        Ok(self.inside[self.id(colour)?]
+            .iter()
+            .map(|&(count, bag)| (count, self.names[bag].as_str()))
+            .collect())
+    }
+
+    /// How many bags in total a bag of the colour must contain. Each bag's
+    /// total is only worked out once.
+    pub fn total_inside(&self, colour: &str) -> Result<u64, GraphError> {
+        let bag = self.id(colour)?;
+        self.total_inside_id(bag)
+            .ok_or_else(|| GraphError::Overflow(colour.to_string()))
+    }
+
+    fn total_inside_id(&self, bag: usize) -> Option<u64> {
+        if let Some(total) = self.total_inside.borrow()[bag] {
+            return Some(total);
+        }
+        let mut total: u64 = 0;
+        for &(count, inner) in &self.inside[bag] {
+            let with_inner = self.total_inside_id(inner)?.checked_add(1)?;
+            total = total.checked_add(count.checked_mul(with_inner)?)?;
+        }
+        self.total_inside.borrow_mut()[bag] = Some(total);
+        Some(total)
+    }
+
+    /// Every colour of bag that can end up holding a bag of the colour,
+    /// sorted. Each bag's containers are only worked out once.
+    pub fn containers(&self, colour: &str) -> Result<Vec<&str>, GraphError> {
+        let mut names: Vec<_> = self
+            .containers_id(self.id(colour)?)
+            .into_iter()
+            .map(|b| self.names[b].as_str())
+            .collect();
+        names.sort_unstable();
+        Ok(names)
+    }
+
+    fn containers_id(&self, bag: usize) -> Vec<usize> {
+        if let Some(containers) = &self.containers.borrow()[bag] {
+            return containers.clone();
+        }
+        let mut containers = self.outside[bag].clone();
+        for &outer in &self.outside[bag] {
+            containers.extend(self.containers_id(outer));
+        }
+        containers.sort_unstable();
+        containers.dedup();
+        self.containers.borrow_mut()[bag] = Some(containers.clone());
+        containers
+    }
+
+    /// Graphviz graph with an edge from each bag to the bags inside it,
+    /// labelled with how many
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph bags {\n");
+        for (bag, inside) in self.inside.iter().enumerate() {
+            for (count, inner) in inside {
+                dot.push_str(&format!(
+                    "    {:?} -> {:?} [label={}];\n",
+                    self.names[bag], self.names[*inner], count
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    fn graph(input: &str) -> Result<BagGraph, GraphError> {
+        BagGraph::new(&parse(input).unwrap())
+    }
+
+    fn graph_file(path: &str) -> BagGraph {
+        graph(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_examples() {
+        let bags = graph_file("data/test.txt");
+        assert_eq!(
+            bags.containers("shiny gold").unwrap(),
+            vec!["bright white", "dark orange", "light red", "muted yellow"]
+        );
+        assert_eq!(bags.total_inside("shiny gold"), Ok(32));
+        assert_eq!(bags.total_inside("faded blue"), Ok(0));
+        assert_eq!(
+            graph_file("data/test2.txt").total_inside("shiny gold"),
+            Ok(126)
+        );
+
+        let bags = graph_file("data/input1.txt");
+        assert_eq!(bags.total_inside("shiny gold"), Ok(34862));
+        // answered again from the memo
+        assert_eq!(bags.total_inside("shiny gold"), Ok(34862));
+    }
+
+    #[test]
+    fn test_errors() {
+        let cycle = graph(
+            "red bags contain 1 blue bag.\n\
+             blue bags contain 2 green bags, 1 white bag.\n\
+             white bags contain 1 red bag.",
+        );
+        assert_eq!(
+            cycle.unwrap_err(),
+            GraphError::Cycle(vec![
+                "red".to_string(),
+                "blue".to_string(),
+                "white".to_string(),
+                "red".to_string()
+            ])
+        );
+        assert_eq!(
+            graph("red bags contain 1 red bag.").unwrap_err(),
+            GraphError::Cycle(vec!["red".to_string(), "red".to_string()])
+        );
+        assert_eq!(
+            graph("red bags contain no other bags.\nred bags contain 1 blue bag.").unwrap_err(),
+            GraphError::Repeated("red".to_string())
+        );
+
+        let bags = graph(
+            "red bags contain 4294967296 blue bags.\nblue bags contain 4294967296 green bags.",
+        )
+        .unwrap();
+        assert_eq!(
+            bags.total_inside("red"),
+            Err(GraphError::Overflow("red".to_string()))
+        );
+        assert_eq!(
+            bags.containers("pink"),
+            Err(GraphError::UnknownColour("pink".to_string()))
+        );
+        // green only shows up inside other bags
+        assert_eq!(bags.contents("green"), Ok(vec![]));
+        assert_eq!(bags.containers("green"), Ok(vec!["blue", "red"]));
+    }
+
+    #[test]
+    fn test_dot() {
+        let bags = graph("red bags contain 1 blue bag, 2 green bags.").unwrap();
+        assert_eq!(
+            bags.to_dot(),
+            "digraph bags {\n    \
+                 \"red\" -> \"blue\" [label=1];\n    \
+                 \"red\" -> \"green\" [label=2];\n\
+             }\n"
+        );
+    }
+}
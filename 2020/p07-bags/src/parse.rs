@@ -0,0 +1,196 @@
+use std::fmt;
+
+/// What a bag of some colour must contain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub colour: String,
+    pub contents: Vec<(u64, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1 based
+    pub line: usize,
+    pub expected: &'static str,
+    /// the rest of the line where it was expected
+    pub found: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: expected {}, found {:?}",
+            self.line, self.expected, self.found
+        )
+    }
+}
+
+/// Reads a rule from left to right:
+///
+/// ```text
+/// rule     = colour " bags contain " contents "."
+/// contents = "no other bags" | item { ", " item }
+/// item     = count " " colour (" bag" | " bags")
+/// ```
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+type Expected = (&'static str, String);
+
+impl<'a> Parser<'a> {
+    fn error<T>(&self, expected: &'static str) -> Result<T, Expected> {
+        Err((expected, self.rest.to_string()))
+    }
+
+    fn eat(&mut self, literal: &'static str) -> bool {
+        match self.rest.strip_prefix(literal) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, literal: &'static str) -> Result<(), Expected> {
+        if self.eat(literal) {
+            Ok(())
+        } else {
+            self.error(literal)
+        }
+    }
+
+    /// Every word up to the next ` bag`
+    fn colour(&mut self) -> Result<String, Expected> {
+        match self.rest.find(" bag") {
+            Some(end) if end > 0 => {
+                let colour = &self.rest[..end];
+                self.rest = &self.rest[end..];
+                Ok(colour.to_string())
+            }
+            _ => self.error("a colour"),
+        }
+    }
+
+    fn count(&mut self) -> Result<u64, Expected> {
+        let end = self
+            .rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest.len());
+        match self.rest[..end].parse() {
+            Ok(n) => {
+                self.rest = &self.rest[end..];
+                Ok(n)
+            }
+            Err(_) => self.error("a count"),
+        }
+    }
+
+    fn item(&mut self) -> Result<(u64, String), Expected> {
+        let count = self.count()?;
+        self.expect(" ")?;
+        let colour = self.colour()?;
+        if !self.eat(" bags") {
+            self.expect(" bag")?;
+        }
+        Ok((count, colour))
+    }
+
+    fn rule(&mut self) -> Result<Rule, Expected> {
+        let colour = self.colour()?;
+        self.expect(" bags contain ")?;
+        let mut contents = Vec::new();
+        if !self.eat("no other bags") {
+            contents.push(self.item()?);
+            while self.eat(", ") {
+                contents.push(self.item()?);
+            }
+        }
+        self.expect(".")?;
+        if !self.rest.is_empty() {
+            return self.error("the end of the line");
+        }
+        Ok(Rule { colour, contents })
+    }
+}
+
+/// One rule per line, like
+/// `light red bags contain 1 bright white bag, 2 muted yellow bags.`
+pub fn parse(input: &str) -> Result<Vec<Rule>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(line, l)| {
+            Parser { rest: l.trim() }
+                .rule()
+                .map_err(|(expected, found)| ParseError {
+                    line: line + 1,
+                    expected,
+                    found,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let rules = parse(
+            "light red bags contain 1 bright white bag, 2 muted yellow bags.\n\
+             faded blue bags contain no other bags.",
+        )
+        .unwrap();
+        assert_eq!(
+            rules,
+            vec![
+                Rule {
+                    colour: "light red".to_string(),
+                    contents: vec![
+                        (1, "bright white".to_string()),
+                        (2, "muted yellow".to_string())
+                    ]
+                },
+                Rule {
+                    colour: "faded blue".to_string(),
+                    contents: vec![]
+                }
+            ]
+        );
+        let rules = parse(&std::fs::read_to_string("data/input1.txt").unwrap()).unwrap();
+        assert_eq!(rules.len(), 594);
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |input: &str| {
+            let e = parse(input).unwrap_err();
+            (e.line, e.expected, e.found)
+        };
+        assert_eq!(
+            error("red bags contain 1 blue bag\n"),
+            (1, ".", "".to_string())
+        );
+        assert_eq!(
+            error("\nred bags contain two blue bags."),
+            (2, "a count", "two blue bags.".to_string())
+        );
+        assert_eq!(
+            error("red bags hold 2 blue bags."),
+            (1, " bags contain ", " bags hold 2 blue bags.".to_string())
+        );
+        assert_eq!(
+            error("red bags contain no other bags. yes"),
+            (1, "the end of the line", " yes".to_string())
+        );
+        assert_eq!(
+            error("red bags contain 2 bags."),
+            (1, "a colour", "bags.".to_string())
+        );
+    }
+}
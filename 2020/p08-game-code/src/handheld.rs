@@ -0,0 +1,312 @@
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Acc(isize),
+    Jmp(isize),
+    Nop(isize),
+}
+
+impl Op {
+    /// Jmp becomes Nop and the other way around, Acc has no inverse
+    pub fn inverse(&self) -> Option<Self> {
+        match self {
+            Op::Acc(_) => None,
+            Op::Nop(num) => Some(Op::Jmp(*num)),
+            Op::Jmp(num) => Some(Op::Nop(*num)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    UnknownOp(String),
+    BadArgument(String),
+    MissingArgument,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnknownOp(op) => write!(f, "unknown operation {:?}", op),
+            ParseError::BadArgument(arg) => write!(f, "bad argument {:?}", arg),
+            ParseError::MissingArgument => write!(f, "missing argument"),
+        }
+    }
+}
+
+impl FromStr for Op {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let op = parts.next().unwrap_or_default();
+        let arg = parts.next().ok_or(ParseError::MissingArgument)?;
+        let num = arg
+            .parse()
+            .map_err(|_| ParseError::BadArgument(arg.to_string()))?;
+        if let Some(extra) = parts.next() {
+            return Err(ParseError::BadArgument(extra.to_string()));
+        }
+        match op {
+            "acc" => Ok(Op::Acc(num)),
+            "jmp" => Ok(Op::Jmp(num)),
+            "nop" => Ok(Op::Nop(num)),
+            _ => Err(ParseError::UnknownOp(op.to_string())),
+        }
+    }
+}
+
+/// A parse error and the line where it happened, starting at 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineError(pub usize, pub ParseError);
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.0, self.1)
+    }
+}
+
+pub type Accum = isize;
+pub type Pointer = isize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// the pointer reached the instruction right after the last one
+    Terminated(Accum),
+    /// the instruction at `pointer` was about to run a second time
+    Looped { acc: Accum, pointer: Pointer },
+    /// the pointer jumped somewhere that is neither an instruction nor the end
+    OutOfBounds { acc: Accum, pointer: Pointer },
+}
+
+/// The single flip of a `Jmp` or `Nop` that makes the program terminate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repair {
+    pub pointer: Pointer,
+    pub original: Op,
+    pub acc: Accum,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Handheld {
+    program: Vec<Op>,
+}
+
+impl FromStr for Handheld {
+    type Err = LineError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let program = s
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(i, l)| l.parse().map_err(|e| LineError(i + 1, e)))
+            .collect::<Result<_, _>>()?;
+        Ok(Handheld { program })
+    }
+}
+
+/// Where the pointer goes after running `op` at `pointer`
+fn next(pointer: Pointer, op: &Op) -> Pointer {
+    match op {
+        Op::Jmp(num) => pointer + num,
+        Op::Acc(_) | Op::Nop(_) => pointer + 1,
+    }
+}
+
+impl Handheld {
+    pub fn new(program: Vec<Op>) -> Handheld {
+        Handheld { program }
+    }
+
+    pub fn program(&self) -> &[Op] {
+        &self.program
+    }
+
+    fn end(&self) -> Pointer {
+        self.program.len() as Pointer
+    }
+
+    fn op(&self, pointer: Pointer) -> Option<&Op> {
+        usize::try_from(pointer)
+            .ok()
+            .and_then(|p| self.program.get(p))
+    }
+
+    /// Runs the program from the start, stopping before any instruction runs
+    /// twice
+    pub fn run(&self) -> Outcome {
+        let mut visited = HashSet::new();
+        let mut acc = 0;
+        let mut pointer = 0;
+        loop {
+            if pointer == self.end() {
+                return Outcome::Terminated(acc);
+            }
+            let op = match self.op(pointer) {
+                Some(op) => op,
+                None => return Outcome::OutOfBounds { acc, pointer },
+            };
+            if !visited.insert(pointer) {
+                return Outcome::Looped { acc, pointer };
+            }
+            if let Op::Acc(num) = op {
+                acc += num;
+            }
+            pointer = next(pointer, op);
+        }
+    }
+
+    /// Control flow graph: for each instruction, the instructions that can
+    /// run right before it. The last entry is for the end of the program.
+    pub fn predecessors(&self) -> Vec<Vec<usize>> {
+        let mut predecessors = vec![vec![]; self.program.len() + 1];
+        for (i, op) in self.program.iter().enumerate() {
+            let n = next(i as Pointer, op);
+            if 0 <= n && n <= self.end() {
+                predecessors[n as usize].push(i);
+            }
+        }
+        predecessors
+    }
+
+    /// For each instruction, whether running the program from it ends up
+    /// terminating. Computed walking the control flow graph backwards from
+    /// the end, so each instruction is looked at once.
+    pub fn terminating(&self) -> Vec<bool> {
+        let predecessors = self.predecessors();
+        let mut terminating = vec![false; self.program.len() + 1];
+        let mut pending = vec![self.program.len()];
+        terminating[self.program.len()] = true;
+        while let Some(i) = pending.pop() {
+            for &p in &predecessors[i] {
+                if !terminating[p] {
+                    terminating[p] = true;
+                    pending.push(p);
+                }
+            }
+        }
+        terminating.pop();
+        terminating
+    }
+
+    /// Finds the `Jmp` or `Nop` that, flipped, makes the program terminate.
+    ///
+    /// Only instructions reached by the original run matter, and since those
+    /// never terminate, flipping one of them fixes the program exactly when
+    /// its new target terminates in the original program. Programs that
+    /// already terminate need no repair.
+    pub fn repair(&self) -> Option<Repair> {
+        if let Outcome::Terminated(_) = self.run() {
+            return None;
+        }
+        let terminating = self.terminating();
+        let terminates =
+            |p: Pointer| p == self.end() || (0 <= p && p < self.end() && terminating[p as usize]);
+
+        let mut visited = HashSet::new();
+        let mut pointer = 0;
+        while let Some(op) = self.op(pointer) {
+            if !visited.insert(pointer) {
+                break;
+            }
+            if let Some(flipped) = op.inverse() {
+                if terminates(next(pointer, &flipped)) {
+                    let mut program = self.program.clone();
+                    program[pointer as usize] = flipped;
+                    return match Handheld::new(program).run() {
+                        Outcome::Terminated(acc) => Some(Repair {
+                            pointer,
+                            original: *op,
+                            acc,
+                        }),
+                        _ => unreachable!("the flipped program terminates"),
+                    };
+                }
+            }
+            pointer = next(pointer, op);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Handheld {
+        std::fs::read_to_string("data/test1.txt")
+            .unwrap()
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        let handheld = example();
+        assert_eq!(handheld.run(), Outcome::Looped { acc: 5, pointer: 1 });
+        assert_eq!(
+            handheld.repair(),
+            Some(Repair {
+                pointer: 7,
+                original: Op::Jmp(-4),
+                acc: 8
+            })
+        );
+    }
+
+    #[test]
+    fn test_analysis() {
+        let handheld = example();
+        let predecessors = handheld.predecessors();
+        assert_eq!(predecessors.len(), 10);
+        assert_eq!(predecessors[1], vec![0, 4]);
+        assert_eq!(predecessors[6], vec![2, 5]);
+        assert_eq!(predecessors[3], vec![7]);
+        assert!(predecessors[0].is_empty());
+        assert_eq!(predecessors[9], vec![8]);
+
+        let terminating = handheld.terminating();
+        assert_eq!(terminating.len(), 9);
+        assert_eq!(terminating.iter().position(|&t| t), Some(8));
+        assert_eq!(terminating.iter().filter(|&&t| t).count(), 1);
+    }
+
+    #[test]
+    fn test_outcomes() {
+        // flipping the first jump only moves the loop
+        let handheld: Handheld = "jmp +0\njmp -1".parse().unwrap();
+        assert_eq!(handheld.run(), Outcome::Looped { acc: 0, pointer: 0 });
+        assert_eq!(handheld.repair(), None);
+
+        let handheld: Handheld = "acc +2\njmp +5".parse().unwrap();
+        assert_eq!(handheld.run(), Outcome::OutOfBounds { acc: 2, pointer: 6 });
+        // jumping out of bounds can be repaired like a loop
+        assert_eq!(handheld.repair().map(|r| (r.pointer, r.acc)), Some((1, 2)));
+
+        // nothing to repair
+        let handheld: Handheld = "acc +2\nnop -1".parse().unwrap();
+        assert_eq!(handheld.run(), Outcome::Terminated(2));
+        assert_eq!(handheld.repair(), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        let parse = |s: &str| s.parse::<Handheld>().unwrap_err();
+        assert_eq!(
+            parse("nop +0\nmul +1"),
+            LineError(2, ParseError::UnknownOp("mul".to_string()))
+        );
+        assert_eq!(parse("acc"), LineError(1, ParseError::MissingArgument));
+        assert_eq!(
+            parse("acc x"),
+            LineError(1, ParseError::BadArgument("x".to_string()))
+        );
+        assert_eq!(Op::Acc(1).inverse(), None);
+        assert_eq!(Op::Nop(3).inverse(), Some(Op::Jmp(3)));
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io::BufRead;
+
+pub type Num = u64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1 based
+    pub line: usize,
+    pub content: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: expected a number, found {:?}",
+            self.line, self.content
+        )
+    }
+}
+
+/// One number per line, read as they are needed
+pub fn numbers<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Num, ParseError>> {
+    reader
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| l.as_ref().map_or(true, |l| !l.trim().is_empty()))
+        .map(|(line, l)| {
+            // a line that can't be read is as bad as one that can't be parsed
+            let l = l.unwrap_or_else(|e| e.to_string());
+            l.trim().parse().map_err(|_| ParseError {
+                line: line + 1,
+                content: l,
+            })
+        })
+}
+
+/// A number that isn't the sum of two different numbers among the ones
+/// right before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Invalid {
+    /// 0 based, counting the preamble
+    pub position: usize,
+    pub value: Num,
+}
+
+/// Checks each number against the `preamble` numbers before it. The sums of
+/// every pair in that window are kept counted, so moving the window along
+/// only adds and removes the sums with the numbers coming in and going out.
+#[derive(Debug, Clone)]
+pub struct XmasStream<I> {
+    input: I,
+    preamble: usize,
+    window: VecDeque<Num>,
+    sums: HashMap<Num, usize>,
+    position: usize,
+}
+
+impl<I: Iterator<Item = Num>> XmasStream<I> {
+    pub fn new<T: IntoIterator<IntoIter = I>>(input: T, preamble: usize) -> XmasStream<I> {
+        XmasStream {
+            input: input.into_iter(),
+            preamble,
+            window: VecDeque::with_capacity(preamble + 1),
+            sums: HashMap::new(),
+            position: 0,
+        }
+    }
+
+    /// Whether `n` is the sum of two different numbers in the window
+    pub fn is_valid(&self, n: Num) -> bool {
+        self.sums.get(&n).is_some_and(|&count| count > 0)
+    }
+
+    fn push(&mut self, n: Num) {
+        for &other in &self.window {
+            if other != n {
+                *self.sums.entry(other + n).or_default() += 1;
+            }
+        }
+        self.window.push_back(n);
+        if self.window.len() > self.preamble {
+            let gone = self.window.pop_front().unwrap();
+            for &other in &self.window {
+                if other != gone {
+                    let sum = other + gone;
+                    let count = self.sums.get_mut(&sum).expect("counted when it came in");
+                    *count -= 1;
+                    if *count == 0 {
+                        self.sums.remove(&sum);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<I: Iterator<Item = Num>> Iterator for XmasStream<I> {
+    type Item = Invalid;
+
+    /// The next invalid number, reading as far as it takes
+    fn next(&mut self) -> Option<Invalid> {
+        loop {
+            let n = self.input.next()?;
+            let position = self.position;
+            let invalid = position >= self.preamble && !self.is_valid(n);
+            self.push(n);
+            self.position += 1;
+            if invalid {
+                return Some(Invalid { position, value: n });
+            }
+        }
+    }
+}
+
+/// At least two numbers in a row adding up to a target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContiguousSum {
+    /// 0 based position of the first number
+    pub start: usize,
+    pub numbers: Vec<Num>,
+}
+
+impl ContiguousSum {
+    /// The smallest and largest numbers added together
+    pub fn weakness(&self) -> Num {
+        let min = self.numbers.iter().min().unwrap();
+        let max = self.numbers.iter().max().unwrap();
+        min + max
+    }
+}
+
+/// The first run of numbers adding up to `target`. The run grows at the back
+/// and shrinks at the front while it adds up to too much, so the numbers are
+/// read once and only the run is kept.
+pub fn find_contiguous_sum<T: IntoIterator<Item = Num>>(
+    numbers: T,
+    target: Num,
+) -> Option<ContiguousSum> {
+    let mut run = VecDeque::new();
+    let mut start = 0;
+    let mut sum = 0;
+    for n in numbers {
+        run.push_back(n);
+        sum += n;
+        while sum > target {
+            sum -= run.pop_front().unwrap();
+            start += 1;
+        }
+        if sum == target && run.len() >= 2 {
+            return Some(ContiguousSum {
+                start,
+                numbers: run.into_iter().collect(),
+            });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::BufReader;
+
+    fn example() -> Vec<Num> {
+        let file = File::open("data/test1.txt").unwrap();
+        numbers(BufReader::new(file))
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        let invalid: Vec<_> = XmasStream::new(example(), 5).collect();
+        assert_eq!(
+            invalid[0],
+            Invalid {
+                position: 14,
+                value: 127
+            }
+        );
+        let sum = find_contiguous_sum(example(), 127).unwrap();
+        assert_eq!(
+            sum,
+            ContiguousSum {
+                start: 2,
+                numbers: vec![15, 25, 47, 40]
+            }
+        );
+        assert_eq!(sum.weakness(), 62);
+    }
+
+    #[test]
+    fn test_preamble() {
+        // 1 to 25 in any order, then the puzzle's checks
+        let mut numbers: Vec<Num> = (1..=25).collect();
+        numbers.swap(0, 24);
+        let check = |n: Num| {
+            let mut numbers = numbers.clone();
+            numbers.push(n);
+            XmasStream::new(numbers, 25).next().is_none()
+        };
+        assert!(check(26));
+        assert!(check(49));
+        assert!(!check(100));
+        assert!(!check(50));
+
+        // every invalid one, not just the first, each checked against the
+        // window before it
+        let invalid: Vec<_> = XmasStream::new(vec![1, 2, 3, 3, 7, 11, 2], 3)
+            .map(|i| i.value)
+            .collect();
+        assert_eq!(invalid, vec![7, 11, 2]);
+        // 3 + 3 isn't two different numbers
+        assert_eq!(XmasStream::new(vec![3, 3, 6], 2).count(), 1);
+    }
+
+    #[test]
+    fn test_streaming() {
+        let input = "1\n2\n\nx\n";
+        let parsed: Vec<_> = numbers(input.as_bytes()).collect();
+        assert_eq!(
+            parsed,
+            vec![
+                Ok(1),
+                Ok(2),
+                Err(ParseError {
+                    line: 4,
+                    content: "x".to_string()
+                })
+            ]
+        );
+        // nothing past what's needed is read
+        let mut read = 0;
+        let counted = (1..).inspect(|_| read += 1);
+        assert_eq!(
+            find_contiguous_sum(counted, 9).unwrap().numbers,
+            vec![2, 3, 4]
+        );
+        assert_eq!(read, 4);
+        assert_eq!(find_contiguous_sum(vec![5, 1], 5), None);
+    }
+}
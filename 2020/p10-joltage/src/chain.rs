@@ -0,0 +1,188 @@
+use number_theory::big::BigUint;
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainError {
+    /// not even using every adapter gets from one joltage to the next
+    Gap { from: u64, to: u64 },
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ChainError::Gap { from, to } = self;
+        write!(f, "no adapter between {} and {} jolts", from, to)
+    }
+}
+
+/// Every adapter sorted from the outlet at 0 jolts to the device, rated
+/// `max_gap` above the highest adapter. An adapter takes any joltage up to
+/// `max_gap` below its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chain<T> {
+    adapters: Vec<T>,
+    /// the outlet, the adapters and the device
+    joltages: Vec<u64>,
+    max_gap: u64,
+}
+
+impl<T: Copy + Ord + Into<u64>> Chain<T> {
+    pub fn new(adapters: &[T], max_gap: u64) -> Result<Chain<T>, ChainError> {
+        let mut adapters = adapters.to_vec();
+        adapters.sort_unstable();
+        let mut joltages: Vec<u64> = Some(0)
+            .into_iter()
+            .chain(adapters.iter().map(|&a| a.into()))
+            .collect();
+        joltages.push(joltages[joltages.len() - 1] + max_gap);
+        if let Some(w) = joltages.windows(2).find(|w| w[1] - w[0] > max_gap) {
+            return Err(ChainError::Gap {
+                from: w[0],
+                to: w[1],
+            });
+        }
+        Ok(Chain {
+            adapters,
+            joltages,
+            max_gap,
+        })
+    }
+
+    pub fn adapters(&self) -> &[T] {
+        &self.adapters
+    }
+
+    pub fn device(&self) -> u64 {
+        self.joltages[self.joltages.len() - 1]
+    }
+
+    /// How many times each difference shows up using every adapter, the
+    /// device included
+    pub fn differences(&self) -> BTreeMap<u64, usize> {
+        let mut histogram = BTreeMap::new();
+        for w in self.joltages.windows(2) {
+            *histogram.entry(w[1] - w[0]).or_default() += 1;
+        }
+        histogram
+    }
+
+    /// The ways to get from the outlet to the device, counted backwards from
+    /// the device: each joltage can go on to any within reach.
+    pub fn arrangements(&self) -> BigUint {
+        let n = self.joltages.len();
+        let mut ways = vec![BigUint::default(); n];
+        ways[n - 1] = BigUint::from(1);
+        for i in (0..n - 1).rev() {
+            ways[i] = (i + 1..n)
+                .take_while(|&j| self.joltages[j] - self.joltages[i] <= self.max_gap)
+                .fold(BigUint::default(), |sum, j| sum.add(&ways[j]));
+        }
+        ways.swap_remove(0)
+    }
+
+    /// Up to `limit` arrangements, each the adapters it uses. Keeping the
+    /// next adapter comes before skipping it, so the first one uses them
+    /// all.
+    pub fn first_arrangements(&self, limit: usize) -> Vec<Vec<T>> {
+        let mut found = Vec::new();
+        let mut used = Vec::new();
+        self.arrange(0, &mut used, limit, &mut found);
+        found
+    }
+
+    /// Arrangements going on from `at` in the joltages. Every adapter can
+    /// reach the device through the ones after it, so nothing is a dead end.
+    fn arrange(&self, at: usize, used: &mut Vec<T>, limit: usize, found: &mut Vec<Vec<T>>) {
+        if found.len() == limit {
+            return;
+        }
+        let device = self.joltages.len() - 1;
+        for next in at + 1..=device {
+            if self.joltages[next] - self.joltages[at] > self.max_gap || found.len() == limit {
+                break;
+            }
+            if next == device {
+                found.push(used.clone());
+            } else {
+                used.push(self.adapters[next - 1]);
+                self.arrange(next, used, limit, found);
+                used.pop();
+            }
+        }
+    }
+
+    /// The adapters every arrangement uses: the ones before and after are
+    /// too far apart to skip them
+    pub fn mandatory(&self) -> Vec<T> {
+        self.joltages
+            .windows(3)
+            .zip(&self.adapters)
+            .filter(|(w, _)| w[2] - w[0] > self.max_gap)
+            .map(|(_, &a)| a)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(path: &str) -> Vec<u8> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|l| l.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_examples() {
+        let chain = Chain::new(&load("data/test1.txt"), 3).unwrap();
+        let differences: Vec<_> = chain.differences().into_iter().collect();
+        assert_eq!(differences, vec![(1, 7), (3, 5)]);
+        assert_eq!(chain.arrangements().to_u64(), Some(8));
+        assert_eq!(chain.device(), 22);
+
+        let chain = Chain::new(&load("data/test2.txt"), 3).unwrap();
+        let differences: Vec<_> = chain.differences().into_iter().collect();
+        assert_eq!(differences, vec![(1, 22), (3, 10)]);
+        assert_eq!(chain.arrangements().to_u64(), Some(19208));
+    }
+
+    #[test]
+    fn test_enumerate() {
+        let chain = Chain::new(&load("data/test1.txt"), 3).unwrap();
+        let all = chain.first_arrangements(100);
+        assert_eq!(all.len(), 8);
+        assert_eq!(all[0], chain.adapters());
+        assert_eq!(all[1], vec![1, 4, 5, 6, 7, 10, 12, 15, 16, 19]);
+        assert_eq!(all[7], vec![1, 4, 7, 10, 12, 15, 16, 19]);
+        assert_eq!(chain.first_arrangements(3).len(), 3);
+
+        // each arrangement holds every mandatory adapter
+        let mandatory = chain.mandatory();
+        assert_eq!(mandatory, vec![1, 4, 7, 10, 12, 15, 16, 19]);
+        assert!(all.iter().all(|a| mandatory.iter().all(|m| a.contains(m))));
+    }
+
+    #[test]
+    fn test_other_gaps() {
+        // up to 4 apart, only 5 can't be skipped as 4 is 5 below the device
+        let chain = Chain::new(&[1u32, 4, 5], 4).unwrap();
+        assert_eq!(chain.device(), 9);
+        assert_eq!(chain.arrangements().to_u64(), Some(3));
+        assert_eq!(chain.mandatory(), vec![5]);
+        assert_eq!(
+            Chain::new(&[1u64, 5], 3),
+            Err(ChainError::Gap { from: 1, to: 5 })
+        );
+
+        // one way per subset of all but the last adapter
+        let adapters: Vec<u16> = (1..=100).collect();
+        let chain = Chain::new(&adapters, 100).unwrap();
+        assert_eq!(
+            chain.arrangements().to_string(),
+            "633825300114114700748351602688"
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum State {
+    Floor,
+    Empty,
+    Occupied,
+}
+
+impl State {
+    pub fn to_char(self) -> char {
+        match self {
+            State::Floor => '.',
+            State::Empty => 'L',
+            State::Occupied => '#',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    BadCell {
+        /// 1 based
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    /// every row must be as wide as the first one
+    Ragged {
+        line: usize,
+        width: usize,
+    },
+    Empty,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::BadCell {
+                line,
+                column,
+                found,
+            } => write!(f, "unexpected {:?} at {}:{}", found, line, column),
+            ParseError::Ragged { line, width } => {
+                write!(f, "line {} is not {} cells wide", line, width)
+            }
+            ParseError::Empty => write!(f, "no seats"),
+        }
+    }
+}
+
+/// The waiting area, row after row in a single vector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<State>,
+}
+
+impl Layout {
+    /// One row per line, `.` being floor, `L` an empty seat and `#` an
+    /// occupied one
+    pub fn parse(input: &str) -> Result<Layout, ParseError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+        for (line, l) in input.lines().enumerate() {
+            let l = l.trim_end();
+            if l.is_empty() {
+                continue;
+            }
+            let row_width = l.chars().count();
+            if *width.get_or_insert(row_width) != row_width {
+                return Err(ParseError::Ragged {
+                    line: line + 1,
+                    width: width.unwrap(),
+                });
+            }
+            for (column, c) in l.chars().enumerate() {
+                cells.push(match c {
+                    '.' => State::Floor,
+                    'L' => State::Empty,
+                    '#' => State::Occupied,
+                    found => {
+                        return Err(ParseError::BadCell {
+                            line: line + 1,
+                            column: column + 1,
+                            found,
+                        })
+                    }
+                });
+            }
+            height += 1;
+        }
+        match width {
+            Some(width) => Ok(Layout {
+                width,
+                height,
+                cells,
+            }),
+            None => Err(ParseError::Empty),
+        }
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Option<State> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        Some(self.cells[y as usize * self.width + x as usize])
+    }
+
+    pub fn occupied(&self) -> usize {
+        self.cells.iter().filter(|&&s| s == State::Occupied).count()
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.cells.chunks(self.width) {
+            let row: String = row.iter().map(|s| s.to_char()).collect();
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let layout = Layout::parse("L.#\n#L.\n").unwrap();
+        assert_eq!((layout.width, layout.height), (3, 2));
+        assert_eq!(layout.get(2, 0), Some(State::Occupied));
+        assert_eq!(layout.get(1, 1), Some(State::Empty));
+        assert_eq!(layout.get(3, 0), None);
+        assert_eq!(layout.get(0, -1), None);
+        assert_eq!(layout.occupied(), 2);
+        assert_eq!(layout.to_string(), "L.#\n#L.\n");
+
+        assert_eq!(
+            Layout::parse("L.\nL#x"),
+            Err(ParseError::Ragged { line: 2, width: 2 })
+        );
+        assert_eq!(
+            Layout::parse("L.\nLx"),
+            Err(ParseError::BadCell {
+                line: 2,
+                column: 2,
+                found: 'x'
+            })
+        );
+        assert_eq!(Layout::parse("\n"), Err(ParseError::Empty));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod layout;
+pub mod replay;
+pub mod sim;
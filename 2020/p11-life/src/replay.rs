@@ -0,0 +1,63 @@
+use crate::sim::{Settled, Simulation};
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+/// Moves the cursor to the top left corner and clears the screen
+const CLEAR: &str = "\x1b[H\x1b[2J";
+
+fn frame(sim: &Simulation, out: &mut impl Write) -> io::Result<()> {
+    write!(out, "{}{}", CLEAR, sim.layout())?;
+    writeln!(
+        out,
+        "generation {}, {} occupied",
+        sim.generation(),
+        sim.occupied()
+    )?;
+    out.flush()
+}
+
+/// Plays the simulation on a terminal, one frame per generation, until it
+/// settles or `max_generations` have gone by
+pub fn replay(
+    sim: &mut Simulation,
+    out: &mut impl Write,
+    delay: Duration,
+    max_generations: usize,
+) -> io::Result<Option<Settled>> {
+    frame(sim, out)?;
+    let mut result = Ok(());
+    let settled = sim.run_with(max_generations, |sim| {
+        if result.is_ok() {
+            thread::sleep(delay);
+            result = frame(sim, out);
+        }
+    });
+    result.map(|_| settled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::Layout;
+    use crate::sim::Rules;
+
+    #[test]
+    fn test_replay() {
+        let layout = Layout::parse("LL").unwrap();
+        let mut sim = Simulation::new(layout, Rules::adjacent().with_tolerance(1));
+        let mut out = Vec::new();
+        let settled = replay(&mut sim, &mut out, Duration::from_millis(0), 10).unwrap();
+        assert_eq!(settled.map(|s| s.period), Some(2));
+        let out = String::from_utf8(out).unwrap();
+        let frames: Vec<_> = out.split(CLEAR).skip(1).collect();
+        assert_eq!(
+            frames,
+            vec![
+                "LL\ngeneration 0, 0 occupied\n",
+                "##\ngeneration 1, 2 occupied\n",
+                "LL\ngeneration 2, 0 occupied\n",
+            ]
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use crate::layout::{Layout, State};
+use std::collections::HashMap;
+
+/// Which seats a passenger looks at before sitting down or leaving
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// the eight cells around the seat
+    Adjacent,
+    /// the first seat in each of the eight directions, however far
+    Visible,
+    /// the first seat in each direction up to the given number of cells away
+    VisibleWithin(usize),
+}
+
+const DIRECTIONS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+impl Neighbourhood {
+    fn reach(self) -> Option<usize> {
+        match self {
+            Neighbourhood::Adjacent => Some(1),
+            Neighbourhood::Visible => None,
+            Neighbourhood::VisibleWithin(reach) => Some(reach),
+        }
+    }
+
+    /// The cell indices of the seats seen from `(x, y)`
+    fn seen_from(self, layout: &Layout, x: usize, y: usize) -> Vec<usize> {
+        let reach = self.reach();
+        DIRECTIONS
+            .iter()
+            .filter_map(|&(dx, dy)| {
+                let (mut cx, mut cy) = (x as isize, y as isize);
+                let mut steps = 0;
+                loop {
+                    if reach == Some(steps) {
+                        return None;
+                    }
+                    cx += dx;
+                    cy += dy;
+                    steps += 1;
+                    match layout.get(cx, cy)? {
+                        State::Floor => (),
+                        _ => return Some(cy as usize * layout.width + cx as usize),
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// How the passengers behave
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    pub neighbourhood: Neighbourhood,
+    /// an occupied seat is left when at least this many of its neighbours
+    /// are occupied
+    pub tolerance: usize,
+    /// an empty seat is taken when at most this many of its neighbours are
+    /// occupied
+    pub take_at_most: usize,
+}
+
+impl Rules {
+    /// Passengers look at the seats right next to theirs
+    pub fn adjacent() -> Rules {
+        Rules {
+            neighbourhood: Neighbourhood::Adjacent,
+            tolerance: 4,
+            take_at_most: 0,
+        }
+    }
+
+    /// Passengers look as far as the first seat in each direction and are
+    /// more tolerant
+    pub fn visible() -> Rules {
+        Rules {
+            neighbourhood: Neighbourhood::Visible,
+            tolerance: 5,
+            take_at_most: 0,
+        }
+    }
+
+    pub fn with_neighbourhood(self, neighbourhood: Neighbourhood) -> Rules {
+        Rules {
+            neighbourhood,
+            ..self
+        }
+    }
+
+    pub fn with_tolerance(self, tolerance: usize) -> Rules {
+        Rules { tolerance, ..self }
+    }
+
+    pub fn with_take_at_most(self, take_at_most: usize) -> Rules {
+        Rules {
+            take_at_most,
+            ..self
+        }
+    }
+}
+
+/// How a simulation ends up repeating itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settled {
+    /// the first generation of the repeating ones
+    pub generation: usize,
+    /// 1 for a fixed point
+    pub period: usize,
+}
+
+impl Settled {
+    pub fn is_fixed(&self) -> bool {
+        self.period == 1
+    }
+}
+
+/// Seats filling and emptying generation after generation. Who each seat
+/// looks at is worked out once, so a generation is just counting.
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    layout: Layout,
+    rules: Rules,
+    /// the cell index of each seat
+    seats: Vec<usize>,
+    /// the seats each seat looks at, by seat index
+    neighbours: Vec<Vec<usize>>,
+    occupied: Vec<bool>,
+    generation: usize,
+}
+
+impl Simulation {
+    pub fn new(layout: Layout, rules: Rules) -> Simulation {
+        let seats: Vec<usize> = (0..layout.cells.len())
+            .filter(|&i| layout.cells[i] != State::Floor)
+            .collect();
+        let mut seat_of = vec![usize::MAX; layout.cells.len()];
+        for (seat, &cell) in seats.iter().enumerate() {
+            seat_of[cell] = seat;
+        }
+        let neighbours = seats
+            .iter()
+            .map(|&cell| {
+                rules
+                    .neighbourhood
+                    .seen_from(&layout, cell % layout.width, cell / layout.width)
+                    .into_iter()
+                    .map(|c| seat_of[c])
+                    .collect()
+            })
+            .collect();
+        let occupied = seats
+            .iter()
+            .map(|&cell| layout.cells[cell] == State::Occupied)
+            .collect();
+        Simulation {
+            layout,
+            rules,
+            seats,
+            neighbours,
+            occupied,
+            generation: 0,
+        }
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn occupied(&self) -> usize {
+        self.occupied.iter().filter(|&&o| o).count()
+    }
+
+    /// The seats as they are now
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// Moves everyone at once, returns how many seats changed
+    pub fn step(&mut self) -> usize {
+        let rules = self.rules;
+        let next: Vec<bool> = self
+            .neighbours
+            .iter()
+            .zip(&self.occupied)
+            .map(|(neighbours, &occupied)| {
+                let around = neighbours.iter().filter(|&&n| self.occupied[n]).count();
+                if occupied {
+                    around < rules.tolerance
+                } else {
+                    around <= rules.take_at_most
+                }
+            })
+            .collect();
+        let mut changed = 0;
+        for (seat, (&now, &before)) in next.iter().zip(&self.occupied).enumerate() {
+            if now != before {
+                changed += 1;
+                self.layout.cells[self.seats[seat]] =
+                    if now { State::Occupied } else { State::Empty };
+            }
+        }
+        self.occupied = next;
+        self.generation += 1;
+        changed
+    }
+
+    /// Steps until a generation repeats an earlier one, None if that takes
+    /// more than `max_generations` steps
+    pub fn run(&mut self, max_generations: usize) -> Option<Settled> {
+        self.run_with(max_generations, |_| ())
+    }
+
+    /// Like `run`, calling `each` after every step
+    pub fn run_with(
+        &mut self,
+        max_generations: usize,
+        mut each: impl FnMut(&Simulation),
+    ) -> Option<Settled> {
+        let mut seen = HashMap::new();
+        seen.insert(self.occupied.clone(), self.generation);
+        for _ in 0..max_generations {
+            let changed = self.step();
+            each(self);
+            if changed == 0 {
+                return Some(Settled {
+                    generation: self.generation - 1,
+                    period: 1,
+                });
+            }
+            if let Some(first) = seen.insert(self.occupied.clone(), self.generation) {
+                return Some(Settled {
+                    generation: first,
+                    period: self.generation - first,
+                });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simulation(input: &str, rules: Rules) -> Simulation {
+        Simulation::new(Layout::parse(input).unwrap(), rules)
+    }
+
+    #[test]
+    fn test_example() {
+        let input = std::fs::read_to_string("data/test1.txt").unwrap();
+        let mut sim = simulation(&input, Rules::adjacent());
+        let settled = sim.run(100).unwrap();
+        assert!(settled.is_fixed());
+        assert_eq!(settled.generation, 5);
+        assert_eq!(sim.occupied(), 37);
+        assert_eq!(sim.layout().occupied(), 37);
+
+        let mut sim = simulation(&input, Rules::visible());
+        assert_eq!(sim.run(100).map(|s| s.generation), Some(6));
+        assert_eq!(sim.occupied(), 26);
+    }
+
+    #[test]
+    fn test_neighbourhoods() {
+        let input = ".......#.\n\
+                     ...#.....\n\
+                     .#.......\n\
+                     .........\n\
+                     ..#L....#\n\
+                     ....#....\n\
+                     .........\n\
+                     #........\n\
+                     ...#.....";
+        let layout = Layout::parse(input).unwrap();
+        let count = |n: Neighbourhood| {
+            n.seen_from(&layout, 3, 4)
+                .iter()
+                .filter(|&&c| layout.cells[c] == State::Occupied)
+                .count()
+        };
+        assert_eq!(count(Neighbourhood::Visible), 8);
+        assert_eq!(count(Neighbourhood::Adjacent), 2);
+        assert_eq!(count(Neighbourhood::VisibleWithin(3)), 5);
+
+        // an empty seat blocks the view of the occupied one behind it
+        let layout = Layout::parse(".L.L.#.#.#.").unwrap();
+        assert_eq!(Neighbourhood::Visible.seen_from(&layout, 1, 0), vec![3]);
+    }
+
+    #[test]
+    fn test_oscillation() {
+        // two seats that can't stand each other
+        let mut sim = simulation("LL", Rules::adjacent().with_tolerance(1));
+        assert_eq!(
+            sim.run(10),
+            Some(Settled {
+                generation: 0,
+                period: 2
+            })
+        );
+        let mut sim = simulation("L.L", Rules::adjacent().with_tolerance(1));
+        assert_eq!(sim.run(10).map(|s| s.period), Some(1));
+        assert_eq!(sim.occupied(), 2);
+        // never settles within the limit
+        let mut sim = simulation("LL", Rules::adjacent().with_tolerance(1));
+        assert_eq!(sim.run(1), None);
+        assert_eq!(sim.generation(), 1);
+
+        let rules = Rules::visible().with_take_at_most(1);
+        let mut sim = simulation("LLL", rules);
+        sim.step();
+        assert_eq!(sim.layout().to_string(), "###\n");
+    }
+}
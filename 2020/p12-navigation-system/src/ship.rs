@@ -0,0 +1,353 @@
+use std::fmt;
+
+/// East and north are positive
+pub type Position = (i64, i64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub fn vector(self) -> Position {
+        match self {
+            Direction::North => (0, 1),
+            Direction::East => (1, 0),
+            Direction::South => (0, -1),
+            Direction::West => (-1, 0),
+        }
+    }
+
+    /// Turned counterclockwise a quarter at a time
+    fn turn(self, quarters: u8) -> Direction {
+        const COUNTERCLOCKWISE: [Direction; 4] = [
+            Direction::North,
+            Direction::West,
+            Direction::South,
+            Direction::East,
+        ];
+        let at = COUNTERCLOCKWISE.iter().position(|&d| d == self).unwrap();
+        COUNTERCLOCKWISE[(at + quarters as usize) % 4]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Move(Direction, i64),
+    /// counterclockwise degrees
+    Left(i64),
+    /// clockwise degrees
+    Right(i64),
+    Forward(i64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1 based
+    pub line: usize,
+    pub content: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: bad instruction {:?}", self.line, self.content)
+    }
+}
+
+/// One instruction per line, an action letter followed by a number, like
+/// `F10` or `R90`
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(line, l)| {
+            let bad = || ParseError {
+                line: line + 1,
+                content: l.to_string(),
+            };
+            let l = l.trim();
+            let action = l.chars().next().ok_or_else(bad)?;
+            let value = l[action.len_utf8()..].parse().map_err(|_| bad())?;
+            Ok(match action {
+                'N' => Instruction::Move(Direction::North, value),
+                'S' => Instruction::Move(Direction::South, value),
+                'E' => Instruction::Move(Direction::East, value),
+                'W' => Instruction::Move(Direction::West, value),
+                'L' => Instruction::Left(value),
+                'R' => Instruction::Right(value),
+                'F' => Instruction::Forward(value),
+                _ => return Err(bad()),
+            })
+        })
+        .collect()
+}
+
+/// How the instructions are read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// moves and turns are for the ship itself
+    Heading,
+    /// moves and turns are for the waypoint, forward goes to the waypoint
+    /// that many times
+    Waypoint,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RotationError {
+    /// index of the instruction
+    pub step: usize,
+    pub degrees: i64,
+}
+
+impl fmt::Display for RotationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "instruction {}: can't turn {} degrees, only multiples of 90",
+            self.step, self.degrees
+        )
+    }
+}
+
+/// Counterclockwise quarter turns, None if not a multiple of 90 degrees
+fn quarters(degrees: i64) -> Option<u8> {
+    if degrees % 90 == 0 {
+        Some((degrees / 90).rem_euclid(4) as u8)
+    } else {
+        None
+    }
+}
+
+fn rotate((x, y): Position, quarters: u8) -> Position {
+    match quarters % 4 {
+        0 => (x, y),
+        1 => (-y, x),
+        2 => (-x, -y),
+        _ => (y, -x),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ship {
+    mode: Mode,
+    position: Position,
+    heading: Direction,
+    /// relative to the ship
+    waypoint: Position,
+    /// the position after each instruction, starting where the ship started
+    trajectory: Vec<Position>,
+    steps: usize,
+}
+
+impl Ship {
+    /// At the origin facing east, the waypoint 10 east and 1 north
+    pub fn new(mode: Mode) -> Ship {
+        Ship {
+            mode,
+            position: (0, 0),
+            heading: Direction::East,
+            waypoint: (10, 1),
+            trajectory: vec![(0, 0)],
+            steps: 0,
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    pub fn heading(&self) -> Direction {
+        self.heading
+    }
+
+    pub fn waypoint(&self) -> Position {
+        self.waypoint
+    }
+
+    pub fn trajectory(&self) -> &[Position] {
+        &self.trajectory
+    }
+
+    /// Manhattan distance from where the ship started
+    pub fn distance(&self) -> i64 {
+        self.position.0.abs() + self.position.1.abs()
+    }
+
+    /// Turns counterclockwise, or clockwise if not `left`
+    fn turn(&mut self, degrees: i64, left: bool) -> Result<(), RotationError> {
+        let quarters = quarters(degrees).ok_or(RotationError {
+            step: self.steps,
+            degrees,
+        })?;
+        let quarters = if left { quarters } else { (4 - quarters) % 4 };
+        match self.mode {
+            Mode::Heading => self.heading = self.heading.turn(quarters),
+            Mode::Waypoint => self.waypoint = rotate(self.waypoint, quarters),
+        }
+        Ok(())
+    }
+
+    pub fn execute(&mut self, instruction: Instruction) -> Result<(), RotationError> {
+        let shift = |(x, y): Position, (dx, dy): Position, n: i64| (x + dx * n, y + dy * n);
+        match (instruction, self.mode) {
+            (Instruction::Move(d, n), Mode::Heading) => {
+                self.position = shift(self.position, d.vector(), n)
+            }
+            (Instruction::Move(d, n), Mode::Waypoint) => {
+                self.waypoint = shift(self.waypoint, d.vector(), n)
+            }
+            (Instruction::Left(degrees), _) => self.turn(degrees, true)?,
+            (Instruction::Right(degrees), _) => self.turn(degrees, false)?,
+            (Instruction::Forward(n), Mode::Heading) => {
+                self.position = shift(self.position, self.heading.vector(), n)
+            }
+            (Instruction::Forward(n), Mode::Waypoint) => {
+                self.position = shift(self.position, self.waypoint, n)
+            }
+        }
+        self.steps += 1;
+        self.trajectory.push(self.position);
+        Ok(())
+    }
+
+    pub fn run(&mut self, instructions: &[Instruction]) -> Result<(), RotationError> {
+        instructions.iter().try_for_each(|&i| self.execute(i))
+    }
+}
+
+/// The route through the positions, scaled down to fit in `width` by
+/// `height` characters with north up. The route is `#`, where it starts `S`
+/// and where it ends `E`.
+pub fn plot(trajectory: &[Position], width: usize, height: usize) -> String {
+    if trajectory.is_empty() || width == 0 || height == 0 {
+        return String::new();
+    }
+    let xs = trajectory.iter().map(|p| p.0);
+    let ys = trajectory.iter().map(|p| p.1);
+    let (min_x, max_x) = (xs.clone().min().unwrap(), xs.max().unwrap());
+    let (min_y, max_y) = (ys.clone().min().unwrap(), ys.max().unwrap());
+    let span_x = (max_x - min_x + 1) as usize;
+    let span_y = (max_y - min_y + 1) as usize;
+    // one cell per position if it fits, the same scale both ways otherwise
+    let scale = span_x.div_ceil(width).max(span_y.div_ceil(height));
+    let (columns, rows) = (span_x.div_ceil(scale), span_y.div_ceil(scale));
+    let cell = |(x, y): Position| {
+        let column = (x - min_x) as usize / scale;
+        let row = rows - 1 - (y - min_y) as usize / scale;
+        (column, row)
+    };
+
+    let mut grid = vec![vec!['.'; columns]; rows];
+    for pair in trajectory.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).max(1);
+        for k in 0..=steps {
+            let x = from.0 + (to.0 - from.0) * k / steps;
+            let y = from.1 + (to.1 - from.1) * k / steps;
+            let (column, row) = cell((x, y));
+            grid[row][column] = '#';
+        }
+    }
+    let (column, row) = cell(trajectory[0]);
+    grid[row][column] = 'S';
+    let (column, row) = cell(trajectory[trajectory.len() - 1]);
+    grid[row][column] = 'E';
+    grid.into_iter()
+        .map(|row| row.into_iter().chain(Some('\n')).collect::<String>())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Vec<Instruction> {
+        parse(&std::fs::read_to_string("data/test1.txt").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_heading() {
+        let mut ship = Ship::new(Mode::Heading);
+        ship.run(&example()).unwrap();
+        assert_eq!(ship.position(), (17, -8));
+        assert_eq!(ship.heading(), Direction::South);
+        assert_eq!(ship.distance(), 25);
+        assert_eq!(
+            ship.trajectory(),
+            &[(0, 0), (10, 0), (10, 3), (17, 3), (17, 3), (17, -8)]
+        );
+    }
+
+    #[test]
+    fn test_waypoint() {
+        let mut ship = Ship::new(Mode::Waypoint);
+        let instructions = example();
+        ship.run(&instructions[..2]).unwrap();
+        assert_eq!((ship.position(), ship.waypoint()), ((100, 10), (10, 4)));
+        ship.run(&instructions[2..4]).unwrap();
+        assert_eq!((ship.position(), ship.waypoint()), ((170, 38), (4, -10)));
+        ship.run(&instructions[4..]).unwrap();
+        assert_eq!(ship.position(), (214, -72));
+        assert_eq!(ship.distance(), 286);
+        // the heading is left alone
+        assert_eq!(ship.heading(), Direction::East);
+    }
+
+    #[test]
+    fn test_rotations() {
+        for &mode in &[Mode::Heading, Mode::Waypoint] {
+            let turned = |instructions: &[Instruction]| {
+                let mut ship = Ship::new(mode);
+                ship.run(instructions).unwrap();
+                (ship.heading(), ship.waypoint())
+            };
+            assert_eq!(
+                turned(&[Instruction::Left(270)]),
+                turned(&[Instruction::Right(90)])
+            );
+            assert_eq!(
+                turned(&[Instruction::Right(-180)]),
+                turned(&[Instruction::Left(180)])
+            );
+            assert_eq!(turned(&[Instruction::Left(720)]), turned(&[]));
+        }
+        let mut ship = Ship::new(Mode::Heading);
+        assert_eq!(
+            ship.run(&[Instruction::Forward(1), Instruction::Right(45)]),
+            Err(RotationError {
+                step: 1,
+                degrees: 45
+            })
+        );
+        assert_eq!(ship.trajectory().len(), 2);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("F10\nL90\n"),
+            Ok(vec![Instruction::Forward(10), Instruction::Left(90)])
+        );
+        assert_eq!(parse("F10\nX3").unwrap_err().line, 2);
+        assert_eq!(parse("F").unwrap_err().line, 1);
+    }
+
+    #[test]
+    fn test_plot() {
+        let mut ship = Ship::new(Mode::Heading);
+        ship.run(&example()).unwrap();
+        let full = plot(ship.trajectory(), 80, 40);
+        assert_eq!(full.lines().count(), 12);
+        assert!(full.lines().all(|l| l.len() == 18));
+        assert_eq!(full.lines().nth(3), Some("S##########......#"));
+        assert_eq!(full.lines().last(), Some(".................E"));
+
+        // scaled down to every 6 positions
+        assert_eq!(plot(ship.trajectory(), 3, 3), "S##\n..E\n");
+        assert_eq!(plot(&[(5, 5)], 3, 3), "E\n");
+    }
+}
@@ -0,0 +1,269 @@
+use number_theory::big::BigUint;
+use number_theory::{crt, gcd, mod_inverse, mod_mul};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Missing(&'static str),
+    BadNumber {
+        /// 1 based
+        line: usize,
+        found: String,
+    },
+    /// a bus can't leave every 0 minutes
+    ZeroInterval {
+        position: usize,
+    },
+    NoBuses,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Missing(what) => write!(f, "no {} line", what),
+            ParseError::BadNumber { line, found } => {
+                write!(f, "line {}: expected a number, found {:?}", line, found)
+            }
+            ParseError::ZeroInterval { position } => {
+                write!(f, "bus at position {} has an interval of 0", position)
+            }
+            ParseError::NoBuses => write!(f, "every bus is out of service"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlignError {
+    /// the bus can't leave its offset after the ones before it do
+    NoSolution { bus: u64, offset: usize },
+}
+
+impl fmt::Display for AlignError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let AlignError::NoSolution { bus, offset } = self;
+        write!(
+            f,
+            "bus {} can never leave {} minutes after the ones before it",
+            bus, offset
+        )
+    }
+}
+
+/// The next bus to catch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Departure {
+    pub bus: u64,
+    pub time: u64,
+    pub wait: u64,
+}
+
+/// A timestamp where every bus leaves its offset later, and how often that
+/// happens again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Aligned {
+    pub timestamp: BigUint,
+    pub period: BigUint,
+}
+
+/// The buses in service, each with its interval and its position in the
+/// list, `x` being a bus out of service
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    buses: Vec<(usize, u64)>,
+}
+
+impl FromStr for Schedule {
+    type Err = ParseError;
+
+    /// Comma separated, like `7,13,x,x,59`
+    fn from_str(s: &str) -> Result<Schedule, ParseError> {
+        let mut buses = Vec::new();
+        for (position, bus) in s.trim().split(',').enumerate() {
+            let bus = bus.trim();
+            if bus == "x" {
+                continue;
+            }
+            let interval: u64 = bus.parse().map_err(|_| ParseError::BadNumber {
+                line: 1,
+                found: bus.to_string(),
+            })?;
+            if interval == 0 {
+                return Err(ParseError::ZeroInterval { position });
+            }
+            buses.push((position, interval));
+        }
+        if buses.is_empty() {
+            return Err(ParseError::NoBuses);
+        }
+        Ok(Schedule { buses })
+    }
+}
+
+impl Schedule {
+    /// Positions and intervals of the buses in service
+    pub fn buses(&self) -> &[(usize, u64)] {
+        &self.buses
+    }
+
+    /// The first bus leaving at or after `after`
+    pub fn earliest_departure(&self, after: u64) -> Departure {
+        self.buses
+            .iter()
+            .map(|&(_, bus)| {
+                let wait = (bus - after % bus) % bus;
+                Departure {
+                    bus,
+                    time: after + wait,
+                    wait,
+                }
+            })
+            .min_by_key(|d| d.wait)
+            .expect("a schedule has buses")
+    }
+
+    /// The earliest timestamp where each bus leaves as many minutes later as
+    /// its position in the list. The intervals need not be coprime.
+    pub fn aligned(&self) -> Result<Aligned, AlignError> {
+        let congruences: Vec<(i64, u64)> = self
+            .buses
+            .iter()
+            .map(|&(position, bus)| (-(position as i64), bus))
+            .collect();
+        match crt(&congruences) {
+            Ok((timestamp, period)) => Ok(Aligned {
+                timestamp: timestamp.into(),
+                period: period.into(),
+            }),
+            // the slow way finds which bus is to blame too
+            Err(_) => self.aligned_big(),
+        }
+    }
+
+    /// Same as `aligned` with no limit on the size of the numbers: the
+    /// solution so far is moved by multiples of the period so far until the
+    /// next bus is in line too.
+    fn aligned_big(&self) -> Result<Aligned, AlignError> {
+        let mut timestamp = BigUint::from(0);
+        let mut period = BigUint::from(1);
+        for &(offset, bus) in &self.buses {
+            // timestamp + period * k == -offset mod bus
+            let target = (bus - (offset as u64) % bus) % bus;
+            let current = timestamp.rem_small(bus);
+            let step = period.rem_small(bus);
+            let g = gcd(step, bus);
+            let diff = (target + bus - current) % bus;
+            if !diff.is_multiple_of(g) {
+                return Err(AlignError::NoSolution { bus, offset });
+            }
+            let reduced = bus / g;
+            let inverse = mod_inverse((step / g) % reduced, reduced).expect("divided by their gcd");
+            let k = mod_mul(diff / g, inverse, reduced);
+            timestamp = timestamp.add(&period.mul_small(k));
+            period = period.mul_small(reduced);
+        }
+        Ok(Aligned { timestamp, period })
+    }
+}
+
+/// What's in the notes: the earliest time you can leave and the schedule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notes {
+    pub earliest: u64,
+    pub schedule: Schedule,
+}
+
+impl FromStr for Notes {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Notes, ParseError> {
+        let mut lines = s.lines().filter(|l| !l.trim().is_empty());
+        let earliest = lines.next().ok_or(ParseError::Missing("timestamp"))?.trim();
+        let earliest = earliest.parse().map_err(|_| ParseError::BadNumber {
+            line: 1,
+            found: earliest.to_string(),
+        })?;
+        let schedule = lines
+            .next()
+            .ok_or(ParseError::Missing("bus"))?
+            .parse()
+            .map_err(|e| match e {
+                ParseError::BadNumber { found, .. } => ParseError::BadNumber { line: 2, found },
+                e => e,
+            })?;
+        Ok(Notes { earliest, schedule })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamp(schedule: &str) -> Result<String, AlignError> {
+        let schedule: Schedule = schedule.parse().unwrap();
+        schedule.aligned().map(|a| a.timestamp.to_string())
+    }
+
+    #[test]
+    fn test_example() {
+        let notes: Notes = std::fs::read_to_string("data/test1.txt")
+            .unwrap()
+            .parse()
+            .unwrap();
+        let departure = notes.schedule.earliest_departure(notes.earliest);
+        assert_eq!((departure.bus, departure.wait), (59, 5));
+        assert_eq!(departure.bus * departure.wait, 295);
+        let aligned = notes.schedule.aligned().unwrap();
+        assert_eq!(aligned.timestamp.to_u64(), Some(1068781));
+        assert_eq!(aligned.period.to_u64(), Some(7 * 13 * 59 * 31 * 19));
+
+        assert_eq!(timestamp("17,x,13,19"), Ok("3417".to_string()));
+        assert_eq!(timestamp("1789,37,47,1889"), Ok("1202161486".to_string()));
+    }
+
+    #[test]
+    fn test_not_coprime() {
+        // t == 0 mod 6 and t + 1 == 0 mod 4 can't both hold
+        assert_eq!(
+            timestamp("6,4"),
+            Err(AlignError::NoSolution { bus: 4, offset: 1 })
+        );
+        // t == 0 mod 6, t + 2 == 0 mod 4
+        let schedule: Schedule = "6,x,4".parse().unwrap();
+        let aligned = schedule.aligned().unwrap();
+        assert_eq!(aligned.timestamp.to_u64(), Some(6));
+        assert_eq!(aligned.period.to_u64(), Some(12));
+    }
+
+    #[test]
+    fn test_big() {
+        // the product of these primes doesn't fit in 64 bits
+        let primes = "4294967291,4294967279,4294967231";
+        let schedule: Schedule = primes.parse().unwrap();
+        let aligned = schedule.aligned().unwrap();
+        assert_eq!(aligned.period.to_u64(), None);
+        for &(offset, bus) in schedule.buses() {
+            assert_eq!((aligned.timestamp.rem_small(bus) + offset as u64) % bus, 0);
+        }
+        // the big path agrees with the fast one where both work
+        let schedule: Schedule = "7,13,x,x,59,x,31,19".parse().unwrap();
+        assert_eq!(schedule.aligned_big(), schedule.aligned());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("x,x".parse::<Schedule>(), Err(ParseError::NoBuses));
+        assert_eq!(
+            "3,0".parse::<Schedule>(),
+            Err(ParseError::ZeroInterval { position: 1 })
+        );
+        assert_eq!(
+            "939\n7,a".parse::<Notes>(),
+            Err(ParseError::BadNumber {
+                line: 2,
+                found: "a".to_string()
+            })
+        );
+        assert_eq!("939".parse::<Notes>(), Err(ParseError::Missing("bus")));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::memory::{MaskedMemory, Region, ADDRESS_BITS};
+use std::fmt;
+
+/// How the mask is applied on writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Version {
+    /// The mask overwrites bits of the value, `X` leaving them unchanged
+    V1,
+    /// The mask overwrites bits of the address, `0` leaving them unchanged
+    /// and `X` making them floating, so the write goes to every address they
+    /// can make
+    V2,
+}
+
+/// A mask like `1XXX0X`, most significant bit first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mask {
+    ones: u64,
+    zeros: u64,
+    floating: u64,
+}
+
+impl Mask {
+    /// The mask that leaves everything as it is for the given version
+    pub fn identity(version: Version) -> Mask {
+        match version {
+            Version::V1 => Mask {
+                ones: 0,
+                zeros: 0,
+                floating: ADDRESS_BITS,
+            },
+            Version::V2 => Mask {
+                ones: 0,
+                zeros: ADDRESS_BITS,
+                floating: 0,
+            },
+        }
+    }
+
+    pub fn value(&self, value: u64) -> u64 {
+        value & !self.zeros | self.ones
+    }
+
+    pub fn address(&self, address: u64) -> Region {
+        Region::new(address | self.ones, self.floating)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Mask(Mask),
+    Write { address: u64, value: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    BadMaskBit(char),
+    MaskLength(usize),
+    /// addresses and values are 36 bits wide
+    TooBig(u64),
+    BadNumber(String),
+    UnknownInstruction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1 based
+    pub line: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            ParseErrorKind::BadMaskBit(c) => write!(f, "bad mask bit {:?}", c),
+            ParseErrorKind::MaskLength(len) => write!(f, "mask has {} bits instead of 36", len),
+            ParseErrorKind::TooBig(n) => write!(f, "{} doesn't fit in 36 bits", n),
+            ParseErrorKind::BadNumber(s) => write!(f, "bad number {:?}", s),
+            ParseErrorKind::UnknownInstruction => write!(f, "unknown instruction"),
+        }
+    }
+}
+
+fn parse_mask(mask: &str) -> Result<Mask, ParseErrorKind> {
+    let len = mask.chars().count();
+    if len != 36 {
+        return Err(ParseErrorKind::MaskLength(len));
+    }
+    let mut parsed = Mask {
+        ones: 0,
+        zeros: 0,
+        floating: 0,
+    };
+    for (bit, c) in mask.chars().rev().enumerate() {
+        let field = match c {
+            '1' => &mut parsed.ones,
+            '0' => &mut parsed.zeros,
+            'X' => &mut parsed.floating,
+            c => return Err(ParseErrorKind::BadMaskBit(c)),
+        };
+        *field |= 1 << bit;
+    }
+    Ok(parsed)
+}
+
+fn parse_number(n: &str) -> Result<u64, ParseErrorKind> {
+    let n = n
+        .trim()
+        .parse()
+        .map_err(|_| ParseErrorKind::BadNumber(n.to_string()))?;
+    if n > ADDRESS_BITS {
+        return Err(ParseErrorKind::TooBig(n));
+    }
+    Ok(n)
+}
+
+fn parse_instruction(line: &str) -> Result<Instruction, ParseErrorKind> {
+    if let Some(mask) = line.strip_prefix("mask = ") {
+        return parse_mask(mask).map(Instruction::Mask);
+    }
+    let write = line
+        .strip_prefix("mem[")
+        .and_then(|rest| rest.split_once("] = "));
+    match write {
+        Some((address, value)) => Ok(Instruction::Write {
+            address: parse_number(address)?,
+            value: parse_number(value)?,
+        }),
+        None => Err(ParseErrorKind::UnknownInstruction),
+    }
+}
+
+/// One instruction per line, either `mask = ...` or `mem[address] = value`
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(line, l)| {
+            parse_instruction(l.trim()).map_err(|kind| ParseError {
+                line: line + 1,
+                kind,
+            })
+        })
+        .collect()
+}
+
+/// Runs the initialization program of the docking port
+#[derive(Debug, Clone)]
+pub struct Decoder {
+    version: Version,
+    mask: Mask,
+    memory: MaskedMemory,
+}
+
+impl Decoder {
+    /// Until the first mask instruction writes go through unchanged
+    pub fn new(version: Version) -> Decoder {
+        Decoder {
+            version,
+            mask: Mask::identity(version),
+            memory: MaskedMemory::new(),
+        }
+    }
+
+    pub fn execute(&mut self, instruction: &Instruction) {
+        match *instruction {
+            Instruction::Mask(mask) => self.mask = mask,
+            Instruction::Write { address, value } => match self.version {
+                Version::V1 => self
+                    .memory
+                    .write(Region::address(address), self.mask.value(value)),
+                Version::V2 => self.memory.write(self.mask.address(address), value),
+            },
+        }
+    }
+
+    pub fn run(&mut self, program: &[Instruction]) {
+        for instruction in program {
+            self.execute(instruction);
+        }
+    }
+
+    pub fn memory(&self) -> &MaskedMemory {
+        &self.memory
+    }
+
+    /// Sum of every value left in memory
+    pub fn sum(&self) -> u128 {
+        self.memory.sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(path: &str, version: Version) -> u128 {
+        let program = parse(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let mut decoder = Decoder::new(version);
+        decoder.run(&program);
+        decoder.sum()
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(run("data/test1.txt", Version::V1), 165);
+        assert_eq!(run("data/test2.txt", Version::V2), 208);
+        assert_eq!(run("data/chris.txt", Version::V1), 15403588588538);
+        assert_eq!(run("data/chris.txt", Version::V2), 3260587250457);
+    }
+
+    #[test]
+    fn test_decoder() {
+        let program = parse("mask = 000000000000000000000000000000X1001X\nmem[42] = 100").unwrap();
+        let mut decoder = Decoder::new(Version::V2);
+        decoder.run(&program);
+        for address in &[26, 27, 58, 59] {
+            assert_eq!(decoder.memory().get(*address), 100);
+        }
+        assert_eq!(decoder.memory().get(42), 0);
+
+        // no mask yet
+        let mut decoder = Decoder::new(Version::V1);
+        decoder.run(&parse("mem[3] = 7").unwrap());
+        assert_eq!(decoder.memory().get(3), 7);
+
+        // a mask full of X floats the whole address space
+        let mut decoder = Decoder::new(Version::V2);
+        decoder.run(&parse(&format!("mask = {}\nmem[0] = 2", "X".repeat(36))).unwrap());
+        assert_eq!(decoder.sum(), 2 << 36);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let kind = |input: &str| parse(input).unwrap_err().kind;
+        assert_eq!(kind("mask = 01X"), ParseErrorKind::MaskLength(3));
+        assert_eq!(
+            kind(&format!("mask = {}2", "0".repeat(35))),
+            ParseErrorKind::BadMaskBit('2')
+        );
+        assert_eq!(
+            kind("mem[68719476736] = 1"),
+            ParseErrorKind::TooBig(1 << 36)
+        );
+        assert_eq!(
+            kind("mem[a] = 1"),
+            ParseErrorKind::BadNumber("a".to_string())
+        );
+        assert_eq!(kind("mem[1] 1"), ParseErrorKind::UnknownInstruction);
+        assert_eq!(parse("mem[1] = 1\nfoo").unwrap_err().line, 2);
+    }
+}
@@ -0,0 +1,177 @@
+/// Addresses are 36 bits wide
+pub const ADDRESS_BITS: u64 = (1 << 36) - 1;
+
+/// The addresses matching `fixed` on every bit not in `floating`, which can
+/// be either 0 or 1. There are 2 to the power of the floating bits of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Region {
+    fixed: u64,
+    floating: u64,
+}
+
+impl Region {
+    pub fn new(fixed: u64, floating: u64) -> Region {
+        let floating = floating & ADDRESS_BITS;
+        Region {
+            fixed: fixed & ADDRESS_BITS & !floating,
+            floating,
+        }
+    }
+
+    pub fn address(address: u64) -> Region {
+        Region::new(address, 0)
+    }
+
+    /// Number of addresses in the region
+    pub fn size(&self) -> u64 {
+        1 << self.floating.count_ones()
+    }
+
+    pub fn contains(&self, address: u64) -> bool {
+        address & !self.floating == self.fixed
+    }
+
+    /// The addresses in both regions
+    pub fn intersection(&self, other: &Region) -> Option<Region> {
+        let both_fixed = !self.floating & !other.floating;
+        if (self.fixed ^ other.fixed) & both_fixed != 0 {
+            return None;
+        }
+        Some(Region::new(
+            self.fixed | other.fixed,
+            self.floating & other.floating,
+        ))
+    }
+
+    /// The addresses in this region but not in `other`, as disjoint regions.
+    /// Each floating bit that `other` fixes splits off the half that differs
+    /// from `other` on it.
+    pub fn difference(&self, other: &Region) -> Vec<Region> {
+        if self.intersection(other).is_none() {
+            return vec![*self];
+        }
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+        let mut split = self.floating & !other.floating;
+        while split != 0 {
+            let bit = split & split.wrapping_neg();
+            split &= !bit;
+            let floating = rest.floating & !bit;
+            pieces.push(Region::new(rest.fixed | (!other.fixed & bit), floating));
+            rest = Region::new(rest.fixed | (other.fixed & bit), floating);
+        }
+        pieces
+    }
+}
+
+/// Memory written a whole region at a time. The regions are kept disjoint,
+/// so a write only cuts what it covers out of the older ones and no address
+/// is ever enumerated.
+#[derive(Debug, Clone, Default)]
+pub struct MaskedMemory {
+    regions: Vec<(Region, u64)>,
+}
+
+impl MaskedMemory {
+    pub fn new() -> MaskedMemory {
+        MaskedMemory::default()
+    }
+
+    pub fn write(&mut self, region: Region, value: u64) {
+        let mut regions = Vec::with_capacity(self.regions.len() + 1);
+        for (old, old_value) in self.regions.drain(..) {
+            regions.extend(old.difference(&region).into_iter().map(|r| (r, old_value)));
+        }
+        if value != 0 {
+            regions.push((region, value));
+        }
+        self.regions = regions;
+    }
+
+    /// Never written addresses are 0
+    pub fn get(&self, address: u64) -> u64 {
+        self.regions
+            .iter()
+            .find(|(region, _)| region.contains(address))
+            .map_or(0, |&(_, value)| value)
+    }
+
+    /// Sum of every value in memory, which may not fit in a `u64` when
+    /// big values are written to big regions
+    pub fn sum(&self) -> u128 {
+        self.regions
+            .iter()
+            .map(|&(region, value)| u128::from(region.size()) * u128::from(value))
+            .sum()
+    }
+
+    /// Disjoint regions holding something other than 0
+    pub fn regions(&self) -> &[(Region, u64)] {
+        &self.regions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn addresses(region: &Region) -> Vec<u64> {
+        (0..64u64).filter(|a| region.contains(*a)).collect()
+    }
+
+    #[test]
+    fn test_regions() {
+        let a = Region::new(0b0001, 0b1010);
+        assert_eq!(a.size(), 4);
+        assert_eq!(addresses(&a), vec![1, 3, 9, 11]);
+        let b = Region::new(0b0010, 0b0101);
+        assert_eq!(a.intersection(&b), Some(Region::address(3)));
+        assert_eq!(a.intersection(&Region::address(2)), None);
+
+        let pieces = a.difference(&b);
+        let mut left: Vec<_> = pieces.iter().flat_map(addresses).collect();
+        left.sort_unstable();
+        assert_eq!(left, vec![1, 9, 11]);
+        assert_eq!(a.difference(&a), vec![]);
+        assert_eq!(a.difference(&Region::address(2)), vec![a]);
+    }
+
+    #[test]
+    fn test_overlapping_writes() {
+        let writes = [
+            (Region::new(0b100, 0b011), 5),
+            (Region::new(0b000, 0b110), 7),
+            (Region::address(6), 0),
+            (Region::new(0b001, 0b10000), 2),
+        ];
+        let mut memory = MaskedMemory::new();
+        let mut expected = HashMap::new();
+        for (region, value) in &writes {
+            memory.write(*region, *value);
+            for address in addresses(region) {
+                expected.insert(address, *value);
+            }
+        }
+        for address in 0..64 {
+            assert_eq!(
+                memory.get(address),
+                expected.get(&address).cloned().unwrap_or(0)
+            );
+        }
+        assert_eq!(
+            memory.sum(),
+            expected.values().map(|&v| u128::from(v)).sum()
+        );
+    }
+
+    #[test]
+    fn test_huge_regions() {
+        let mut memory = MaskedMemory::new();
+        memory.write(Region::new(0, ADDRESS_BITS), ADDRESS_BITS);
+        assert_eq!(memory.sum(), (1 << 36) * u128::from(ADDRESS_BITS));
+        memory.write(Region::new(1 << 35, ADDRESS_BITS >> 1), 0);
+        assert_eq!(memory.sum(), (1 << 35) * u128::from(ADDRESS_BITS));
+        assert_eq!(memory.regions().len(), 1);
+    }
+}
@@ -0,0 +1,166 @@
+use std::fmt;
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    BadNumber(String),
+    Empty,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::BadNumber(found) => write!(f, "expected a number, found {:?}", found),
+            ParseError::Empty => write!(f, "no starting numbers"),
+        }
+    }
+}
+
+/// Comma separated starting numbers, like `0,3,6`
+pub fn parse_starting(input: &str) -> Result<Vec<u32>, ParseError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    input
+        .split(',')
+        .map(|n| {
+            let n = n.trim();
+            n.parse().map_err(|_| ParseError::BadNumber(n.to_string()))
+        })
+        .collect()
+}
+
+/// The numbers spoken in the game, one per turn. After the starting numbers
+/// each one is how many turns apart the last number was spoken the last two
+/// times, or 0 if that was its first time.
+#[derive(Debug, Clone)]
+pub struct MemoryGame {
+    starting: Vec<u32>,
+    /// for each number the turn it was last spoken on, not counting the
+    /// latest turn, 0 if never
+    last_turn: Vec<u32>,
+    /// turns played so far
+    turn: u32,
+    /// what was said on the latest turn
+    last: u32,
+}
+
+impl MemoryGame {
+    /// A game expected to last about `turns` turns, so nothing needs to grow.
+    /// The numbers spoken are never bigger than the turn count, past the
+    /// starting ones.
+    pub fn new(starting: &[u32], turns: usize) -> MemoryGame {
+        let largest = starting.iter().max().map_or(0, |&n| n as usize + 1);
+        MemoryGame {
+            starting: starting.to_vec(),
+            last_turn: vec![0; turns.max(largest)],
+            turn: 0,
+            last: 0,
+        }
+    }
+
+    /// Turns played so far
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    /// The number spoken on `turn`, counting from 1, which must be past the
+    /// turns played so far
+    pub fn spoken_on(&mut self, turn: u32) -> Option<u32> {
+        let ahead = turn.checked_sub(self.turn + 1)?;
+        self.nth(ahead as usize)
+    }
+
+    /// Writes what's spoken on each of the next `turns` turns, one number per
+    /// line
+    pub fn export<W: Write>(&mut self, turns: usize, out: W) -> io::Result<()> {
+        let mut out = io::BufWriter::new(out);
+        for n in self.take(turns) {
+            writeln!(out, "{}", n)?;
+        }
+        out.flush()
+    }
+}
+
+impl Iterator for MemoryGame {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        let spoken = match self.starting.get(self.turn as usize) {
+            Some(&n) => n,
+            None => match self.last_turn[self.last as usize] {
+                0 => 0,
+                before => self.turn - before,
+            },
+        };
+        // only now is the previous number's latest turn in the past
+        if self.turn > 0 {
+            self.last_turn[self.last as usize] = self.turn;
+        }
+        if spoken as usize >= self.last_turn.len() {
+            self.last_turn.resize(spoken as usize + 1, 0);
+        }
+        self.turn += 1;
+        self.last = spoken;
+        Some(spoken)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence() {
+        let spoken: Vec<_> = MemoryGame::new(&[0, 3, 6], 10).take(10).collect();
+        assert_eq!(spoken, vec![0, 3, 6, 0, 3, 3, 1, 0, 4, 0]);
+
+        let mut exported = Vec::new();
+        MemoryGame::new(&[0, 3, 6], 0)
+            .export(5, &mut exported)
+            .unwrap();
+        assert_eq!(exported, b"0\n3\n6\n0\n3\n");
+    }
+
+    #[test]
+    fn test_examples() {
+        let examples = [
+            ("0,3,6", 436, 175594),
+            ("1,3,2", 1, 2578),
+            ("2,1,3", 10, 3544142),
+            ("1,2,3", 27, 261214),
+            ("2,3,1", 78, 6895259),
+            ("3,2,1", 438, 18),
+            ("3,1,2", 1836, 362),
+        ];
+        for (i, &(starting, year, long)) in examples.iter().enumerate() {
+            let starting = parse_starting(starting).unwrap();
+            let mut game = MemoryGame::new(&starting, 30_000_000);
+            assert_eq!(game.spoken_on(2020), Some(year));
+            // the long game is slow without optimisations, one is enough
+            if i == 0 {
+                assert_eq!(game.spoken_on(30_000_000), Some(long));
+            }
+        }
+    }
+
+    #[test]
+    fn test_queries() {
+        let mut game = MemoryGame::new(&[0, 3, 6], 0);
+        assert_eq!(game.spoken_on(9), Some(4));
+        assert_eq!(game.turn(), 9);
+        // the past is gone
+        assert_eq!(game.spoken_on(9), None);
+        assert_eq!(game.spoken_on(0), None);
+        // big starting numbers are fine too
+        let mut game = MemoryGame::new(&[1000, 1000], 4);
+        assert_eq!(game.spoken_on(4), Some(0));
+
+        assert_eq!(parse_starting(" "), Err(ParseError::Empty));
+        assert_eq!(
+            parse_starting("1,x"),
+            Err(ParseError::BadNumber("x".to_string()))
+        );
+    }
+}
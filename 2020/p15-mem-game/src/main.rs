@@ -1,38 +1,31 @@
-use std::collections::HashMap;
+use p15_mem_game::game::{parse_starting, MemoryGame};
 
 fn main() {
-    let nums = "0,12,6,13,20,1,17";
-    let mut last_turns = HashMap::new();
-    let mut last_said = 0;
-    for (i, n) in nums.split(",").enumerate() {
-        let n = n.parse::<usize>().unwrap();
-        last_turns.insert(n, [i + 1, 0]);
-        last_said = n;
-    }
-
-    let n = last_turns.len();
-    for turn in n + 1..=30000000 {
-        last_said = if let Some(lasts) = last_turns.get(&last_said) {
-            if lasts[1] == 0 {
-                0
-            } else {
-                lasts[0] - lasts[1]
-            }
-        } else {
-            0
-        };
-
-        // println!("turn {} say {}", turn, last_said);
-
-        if let Some(lasts) = last_turns.get_mut(&last_said) {
-            lasts[1] = lasts[0];
-            lasts[0] = turn;
-
-        } else {
-            last_turns.insert(last_said, [turn, 0]);
+    let mut args = std::env::args().skip(1);
+    let starting = args
+        .next()
+        .unwrap_or_else(|| "0,12,6,13,20,1,17".to_string());
+    let starting = match parse_starting(&starting) {
+        Ok(starting) => starting,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
         }
+    };
 
+    // `export N` writes the first N numbers instead
+    if args.next().as_deref() == Some("export") {
+        let turns = args
+            .next()
+            .and_then(|n| n.parse().ok())
+            .expect("Bad turn count");
+        MemoryGame::new(&starting, turns)
+            .export(turns, std::io::stdout())
+            .expect("Can't write");
+        return;
     }
 
-    println!("last said: {}", last_said);
+    let mut game = MemoryGame::new(&starting, 30_000_000);
+    println!("Part 1 {}", game.spoken_on(2020).unwrap());
+    println!("Part 2 {}", game.spoken_on(30_000_000).unwrap());
 }
@@ -0,0 +1,206 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+pub type Num = u16;
+pub type Ticket = Vec<Num>;
+
+/// A ticket field and the values it can take, like
+/// `class: 1-3 or 5-7`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub name: String,
+    pub ranges: Vec<RangeInclusive<Num>>,
+}
+
+impl Rule {
+    pub fn contains(&self, value: Num) -> bool {
+        self.ranges.iter().any(|r| r.contains(&value))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    BadRule(String),
+    BadTicket(String),
+    /// the section with the given heading is missing
+    Missing(&'static str),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::BadRule(l) => write!(f, "bad rule {:?}", l),
+            ParseError::BadTicket(l) => write!(f, "bad ticket {:?}", l),
+            ParseError::Missing(heading) => write!(f, "no {:?} section", heading),
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = || ParseError::BadRule(s.to_string());
+        let (name, ranges) = s.split_once(": ").ok_or_else(bad)?;
+        let ranges = ranges
+            .split(" or ")
+            .map(|r| {
+                let (start, end) = r.trim().split_once('-').ok_or_else(bad)?;
+                let start = start.parse().map_err(|_| bad())?;
+                let end = end.parse().map_err(|_| bad())?;
+                Ok(start..=end)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Rule {
+            name: name.to_string(),
+            ranges,
+        })
+    }
+}
+
+fn parse_ticket(line: &str) -> Result<Ticket, ParseError> {
+    line.split(',')
+        .map(|n| {
+            n.trim()
+                .parse()
+                .map_err(|_| ParseError::BadTicket(line.to_string()))
+        })
+        .collect()
+}
+
+/// A nearby ticket value that fits no rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidValue {
+    /// index in the nearby tickets
+    pub ticket: usize,
+    pub field: usize,
+    pub value: Num,
+}
+
+impl fmt::Display for InvalidValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ticket {} field {}: {} fits no rule",
+            self.ticket, self.field, self.value
+        )
+    }
+}
+
+/// The rules, your ticket and the nearby tickets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notes {
+    pub rules: Vec<Rule>,
+    pub mine: Ticket,
+    pub nearby: Vec<Ticket>,
+}
+
+impl Notes {
+    pub fn parse(input: &str) -> Result<Notes, ParseError> {
+        let input = input.replace("\r\n", "\n");
+        let mut sections = input.split("\n\n");
+        let rules = sections
+            .next()
+            .ok_or(ParseError::Missing("rules"))?
+            .lines()
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+        let mut tickets = |heading: &'static str| -> Result<Vec<Ticket>, ParseError> {
+            let mut lines = sections.next().ok_or(ParseError::Missing(heading))?.lines();
+            if lines.next().map(str::trim) != Some(heading) {
+                return Err(ParseError::Missing(heading));
+            }
+            lines
+                .filter(|l| !l.trim().is_empty())
+                .map(parse_ticket)
+                .collect()
+        };
+        let mine = tickets("your ticket:")?
+            .pop()
+            .ok_or(ParseError::Missing("your ticket:"))?;
+        let nearby = tickets("nearby tickets:")?;
+        Ok(Notes {
+            rules,
+            mine,
+            nearby,
+        })
+    }
+
+    fn fits_any(&self, value: Num) -> bool {
+        self.rules.iter().any(|r| r.contains(value))
+    }
+
+    /// Every value of the nearby tickets that fits no rule
+    pub fn invalid_values(&self) -> Vec<InvalidValue> {
+        self.nearby
+            .iter()
+            .enumerate()
+            .flat_map(|(ticket, values)| {
+                values
+                    .iter()
+                    .enumerate()
+                    .filter(move |&(_, &value)| !self.fits_any(value))
+                    .map(move |(field, &value)| InvalidValue {
+                        ticket,
+                        field,
+                        value,
+                    })
+            })
+            .collect()
+    }
+
+    /// Sum of the invalid values
+    pub fn error_rate(&self) -> u64 {
+        self.invalid_values()
+            .iter()
+            .map(|v| u64::from(v.value))
+            .sum()
+    }
+
+    /// The nearby tickets with no invalid value
+    pub fn valid_tickets(&self) -> impl Iterator<Item = &Ticket> {
+        self.nearby
+            .iter()
+            .filter(move |t| t.iter().all(|&v| self.fits_any(v)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validity() {
+        let notes = Notes::parse(&std::fs::read_to_string("data/test1.txt").unwrap()).unwrap();
+        assert_eq!(notes.rules.len(), 3);
+        assert_eq!(notes.mine, vec![7, 1, 14]);
+        let invalid: Vec<_> = notes
+            .invalid_values()
+            .iter()
+            .map(|v| (v.ticket, v.field, v.value))
+            .collect();
+        assert_eq!(invalid, vec![(1, 1, 4), (2, 0, 55), (3, 2, 12)]);
+        assert_eq!(notes.error_rate(), 71);
+        assert_eq!(notes.valid_tickets().count(), 1);
+    }
+
+    #[test]
+    fn test_parse() {
+        let rule: Rule = "odd ones: 1-1 or 3-3 or 5-9".parse().unwrap();
+        assert_eq!(rule.ranges, vec![1..=1, 3..=3, 5..=9]);
+        assert!(rule.contains(7) && !rule.contains(4));
+        assert_eq!(
+            "class 1-3".parse::<Rule>(),
+            Err(ParseError::BadRule("class 1-3".to_string()))
+        );
+        assert_eq!(
+            Notes::parse("a: 1-2\n\nyour ticket:\n1,x\n\nnearby tickets:\n"),
+            Err(ParseError::BadTicket("1,x".to_string()))
+        );
+        assert_eq!(
+            Notes::parse("a: 1-2\n\nyour ticket:\n1"),
+            Err(ParseError::Missing("nearby tickets:"))
+        );
+    }
+}
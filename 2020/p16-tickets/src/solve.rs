@@ -0,0 +1,264 @@
+use crate::notes::Notes;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// some ticket has a different number of fields than there are rules
+    FieldCount { rules: usize, fields: usize },
+    /// the valid values of these fields only fit fewer rules than there are
+    /// fields, so some field can't get a rule
+    Impossible {
+        fields: Vec<usize>,
+        rules: Vec<String>,
+    },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::FieldCount { rules, fields } => {
+                write!(f, "{} rules for tickets with {} fields", rules, fields)
+            }
+            SolveError::Impossible { fields, rules } => write!(
+                f,
+                "fields {:?} only fit the {} rules {:?}",
+                fields,
+                rules.len(),
+                rules
+            ),
+        }
+    }
+}
+
+/// Which rule each field of the tickets follows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assignment {
+    /// `rule_of[field]` is the index of the rule the field follows
+    rule_of: Vec<usize>,
+    /// the other rules each field could follow in another assignment
+    alternatives: Vec<Vec<usize>>,
+}
+
+impl Assignment {
+    pub fn rule_of(&self, field: usize) -> usize {
+        self.rule_of[field]
+    }
+
+    pub fn field_of(&self, rule: usize) -> usize {
+        self.rule_of
+            .iter()
+            .position(|&r| r == rule)
+            .expect("every rule has a field")
+    }
+
+    /// Whether this is the only assignment that fits the tickets
+    pub fn is_unique(&self) -> bool {
+        self.alternatives.iter().all(Vec::is_empty)
+    }
+
+    /// The other rules the field could follow, making the assignment
+    /// ambiguous if there are any
+    pub fn alternatives(&self, field: usize) -> &[usize] {
+        &self.alternatives[field]
+    }
+
+    /// One line for each field that could follow more than one rule
+    pub fn explain(&self, notes: &Notes) -> String {
+        let name = |rule: usize| notes.rules[rule].name.as_str();
+        self.alternatives
+            .iter()
+            .enumerate()
+            .filter(|(_, others)| !others.is_empty())
+            .map(|(field, others)| {
+                let others: Vec<_> = others.iter().map(|&r| name(r)).collect();
+                format!(
+                    "field {} is {} but could be {}\n",
+                    field,
+                    name(self.rule_of[field]),
+                    others.join(" or ")
+                )
+            })
+            .collect()
+    }
+}
+
+/// Tries to give `field` a rule, moving other fields to another of their
+/// rules along an augmenting path
+fn augment(
+    field: usize,
+    candidates: &[Vec<usize>],
+    owner: &mut [Option<usize>],
+    seen: &mut [bool],
+) -> bool {
+    for &rule in &candidates[field] {
+        if seen[rule] {
+            continue;
+        }
+        seen[rule] = true;
+        let free = match owner[rule] {
+            None => true,
+            Some(other) => augment(other, candidates, owner, seen),
+        };
+        if free {
+            owner[rule] = Some(field);
+            return true;
+        }
+    }
+    false
+}
+
+/// Matches each field with a rule all valid tickets fit. This is a
+/// bipartite matching, so it works even when no field is left with a single
+/// possible rule.
+pub fn solve(notes: &Notes) -> Result<Assignment, SolveError> {
+    let n = notes.rules.len();
+    let tickets: Vec<_> = notes.valid_tickets().collect();
+    if let Some(t) = std::iter::once(&notes.mine)
+        .chain(tickets.iter().cloned())
+        .find(|t| t.len() != n)
+    {
+        return Err(SolveError::FieldCount {
+            rules: n,
+            fields: t.len(),
+        });
+    }
+    let candidates: Vec<Vec<usize>> = (0..n)
+        .map(|field| {
+            (0..n)
+                .filter(|&rule| tickets.iter().all(|t| notes.rules[rule].contains(t[field])))
+                .collect()
+        })
+        .collect();
+
+    let mut owner = vec![None; n];
+    for field in 0..n {
+        if !augment(field, &candidates, &mut owner, &mut vec![false; n]) {
+            return Err(impossible(field, &candidates, &owner, notes));
+        }
+    }
+    let mut rule_of = vec![0; n];
+    for (rule, field) in owner.iter().enumerate() {
+        rule_of[field.expect("perfect matching")] = rule;
+    }
+
+    // the field can follow another rule if giving it that rule still lets
+    // the field that had it move elsewhere
+    let alternatives = (0..n)
+        .map(|field| {
+            candidates[field]
+                .iter()
+                .cloned()
+                .filter(|&rule| rule != rule_of[field])
+                .filter(|&rule| {
+                    let mut owner = owner.clone();
+                    let displaced = owner[rule].expect("perfect matching");
+                    owner[rule] = Some(field);
+                    owner[rule_of[field]] = None;
+                    let mut seen = vec![false; n];
+                    seen[rule] = true;
+                    augment(displaced, &candidates, &mut owner, &mut seen)
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(Assignment {
+        rule_of,
+        alternatives,
+    })
+}
+
+/// The fields reachable from `unmatched` by alternating paths all compete
+/// for the rules reached on the way, which are one fewer than them
+fn impossible(
+    unmatched: usize,
+    candidates: &[Vec<usize>],
+    owner: &[Option<usize>],
+    notes: &Notes,
+) -> SolveError {
+    let mut fields = vec![unmatched];
+    let mut rules = Vec::new();
+    let mut next = 0;
+    while next < fields.len() {
+        for &rule in &candidates[fields[next]] {
+            if !rules.contains(&rule) {
+                rules.push(rule);
+                if let Some(field) = owner[rule] {
+                    fields.push(field);
+                }
+            }
+        }
+        next += 1;
+    }
+    fields.sort_unstable();
+    rules.sort_unstable();
+    SolveError::Impossible {
+        fields,
+        rules: rules
+            .into_iter()
+            .map(|r| notes.rules[r].name.clone())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_notes(rules: &str, tickets: &str) -> Notes {
+        Notes::parse(&format!(
+            "{}\n\nyour ticket:\n{}\n\nnearby tickets:\n{}",
+            rules,
+            tickets.lines().next().unwrap(),
+            tickets
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        let notes = Notes::parse(&std::fs::read_to_string("data/test2.txt").unwrap()).unwrap();
+        let assignment = solve(&notes).unwrap();
+        let names: Vec<_> = (0..3)
+            .map(|f| notes.rules[assignment.rule_of(f)].name.as_str())
+            .collect();
+        assert_eq!(names, vec!["row", "class", "seat"]);
+        assert!(assignment.is_unique());
+        assert_eq!(assignment.explain(&notes), "");
+    }
+
+    #[test]
+    fn test_ambiguous() {
+        // once field 0 takes c nothing tells fields 1 and 2 apart
+        let notes = parse_notes("a: 1-5\nb: 1-5\nc: 1-10", "7,1,2\n8,3,4\n99,1,1");
+        let assignment = solve(&notes).unwrap();
+        assert_eq!(assignment.rule_of(0), 2);
+        assert!(!assignment.is_unique());
+        assert_eq!(assignment.alternatives(0), &[] as &[usize]);
+        assert_eq!(assignment.alternatives(1), &[1 - assignment.rule_of(1)]);
+        let explanation = assignment.explain(&notes);
+        assert_eq!(explanation.lines().count(), 2);
+        assert!(explanation.starts_with("field 1 is "));
+    }
+
+    #[test]
+    fn test_impossible() {
+        // three fields for the two rules up to 5
+        let notes = parse_notes("a: 1-5\nb: 1-5\nc: 6-10", "1,2,3\n4,5,1");
+        assert_eq!(
+            solve(&notes),
+            Err(SolveError::Impossible {
+                fields: vec![0, 1, 2],
+                rules: vec!["a".to_string(), "b".to_string()]
+            })
+        );
+        let notes = parse_notes("a: 1-5\nb: 1-5", "1,2,3");
+        assert_eq!(
+            solve(&notes),
+            Err(SolveError::FieldCount {
+                rules: 2,
+                fields: 3
+            })
+        );
+    }
+}
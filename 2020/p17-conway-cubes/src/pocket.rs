@@ -0,0 +1,274 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+pub type Position = i32;
+pub type Point = Vec<Position>;
+
+/// The starting slice is along the first two dimensions
+const SLICE_DIMS: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    BadCube {
+        /// 1 based
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    /// the starting slice is two dimensional already
+    TooFewDimensions(usize),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::BadCube {
+                line,
+                column,
+                found,
+            } => write!(f, "unexpected {:?} at {}:{}", found, line, column),
+            ParseError::TooFewDimensions(dims) => {
+                write!(f, "{} dimensions can't hold the starting slice", dims)
+            }
+        }
+    }
+}
+
+/// A pocket dimension of any number of dimensions, starting from a flat
+/// slice where every coordinate but the first two is 0.
+///
+/// Every generation is then the same seen from either side of the slice in
+/// each extra dimension, so only the cubes with no negative extra coordinate
+/// are kept, each standing for all its mirror images.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pocket {
+    dims: usize,
+    active: HashSet<Point>,
+}
+
+impl Pocket {
+    /// The starting slice, `#` being an active cube and `.` an inactive one
+    pub fn parse(slice: &str, dims: usize) -> Result<Pocket, ParseError> {
+        if dims < SLICE_DIMS {
+            return Err(ParseError::TooFewDimensions(dims));
+        }
+        let mut active = HashSet::new();
+        for (y, l) in slice.lines().enumerate() {
+            for (x, c) in l.trim_end().chars().enumerate() {
+                match c {
+                    '#' => {
+                        let mut point = vec![0; dims];
+                        point[0] = x as Position;
+                        point[1] = y as Position;
+                        active.insert(point);
+                    }
+                    '.' => (),
+                    found => {
+                        return Err(ParseError::BadCube {
+                            line: y + 1,
+                            column: x + 1,
+                            found,
+                        })
+                    }
+                }
+            }
+        }
+        Ok(Pocket { dims, active })
+    }
+
+    pub fn dims(&self) -> usize {
+        self.dims
+    }
+
+    /// The point standing for `point` and its mirror images
+    fn canonical(&self, point: &[Position]) -> Point {
+        point
+            .iter()
+            .enumerate()
+            .map(|(i, &p)| if i < SLICE_DIMS { p } else { p.abs() })
+            .collect()
+    }
+
+    /// How many cubes a kept one stands for
+    fn images(point: &[Position]) -> usize {
+        1 << point.iter().skip(SLICE_DIMS).filter(|&&p| p != 0).count()
+    }
+
+    pub fn is_active(&self, point: &[Position]) -> bool {
+        point.len() == self.dims && self.active.contains(&self.canonical(point))
+    }
+
+    /// Number of active cubes, mirror images included
+    pub fn active(&self) -> usize {
+        self.active.iter().map(|p| Pocket::images(p)).sum()
+    }
+
+    /// For each kept cube around an active one, how many of the active cube
+    /// and its mirror images are next to it. In an extra dimension a cube at
+    /// 1 is next to 0 twice, once as itself and once as its image at -1.
+    fn add_neighbour_counts(
+        point: &[Position],
+        next: &mut Point,
+        weight: usize,
+        all_stay: bool,
+        counts: &mut HashMap<Point, usize>,
+    ) {
+        let i = next.len();
+        if i == point.len() {
+            if !all_stay {
+                *counts.entry(next.clone()).or_default() += weight;
+            }
+            return;
+        }
+        let p = point[i];
+        for delta in -1..=1 {
+            let n = p + delta;
+            let extra = i >= SLICE_DIMS;
+            if extra && n < 0 {
+                continue;
+            }
+            let w = if extra && p == 1 && n == 0 { 2 } else { 1 };
+            next.push(n);
+            Pocket::add_neighbour_counts(point, next, weight * w, all_stay && delta == 0, counts);
+            next.pop();
+        }
+    }
+
+    /// An active cube stays active with 2 or 3 active neighbours, an
+    /// inactive one becomes active with exactly 3. Neighbours are counted
+    /// from the active cubes, so only cubes next to one are looked at.
+    pub fn step(&mut self) {
+        let mut counts: HashMap<Point, usize> = HashMap::new();
+        let mut next = Vec::with_capacity(self.dims);
+        for point in &self.active {
+            Pocket::add_neighbour_counts(point, &mut next, 1, true, &mut counts);
+        }
+        let active = &self.active;
+        self.active = counts
+            .into_iter()
+            .filter(|(point, n)| *n == 3 || (*n == 2 && active.contains(point)))
+            .map(|(point, _)| point)
+            .collect();
+    }
+
+    pub fn run(&mut self, cycles: usize) {
+        for _ in 0..cycles {
+            self.step();
+        }
+    }
+
+    /// The range of each coordinate holding active cubes, None if there are
+    /// none
+    pub fn bounds(&self) -> Option<Vec<(Position, Position)>> {
+        let mut points = self.active.iter();
+        let first = points.next()?;
+        let mut bounds: Vec<_> = first.iter().map(|&p| (p, p)).collect();
+        for point in points {
+            for (b, &p) in bounds.iter_mut().zip(point) {
+                *b = (b.0.min(p), b.1.max(p));
+            }
+        }
+        for b in bounds.iter_mut().skip(SLICE_DIMS) {
+            *b = (-b.1, b.1);
+        }
+        Some(bounds)
+    }
+
+    /// The plane along the `horizontal` and `vertical` axes through `at`,
+    /// whose coordinates on those two axes are ignored. `#` is an active
+    /// cube and `.` an inactive one, over the whole range of both axes.
+    pub fn paint(&self, horizontal: usize, vertical: usize, at: &[Position]) -> String {
+        let bounds = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return String::new(),
+        };
+        let mut point = at.to_vec();
+        point.resize(self.dims, 0);
+        let mut out = String::new();
+        for v in bounds[vertical].0..=bounds[vertical].1 {
+            point[vertical] = v;
+            for h in bounds[horizontal].0..=bounds[horizontal].1 {
+                point[horizontal] = h;
+                out.push(if self.is_active(&point) { '#' } else { '.' });
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example(dims: usize) -> Pocket {
+        Pocket::parse(&std::fs::read_to_string("data/test1.txt").unwrap(), dims).unwrap()
+    }
+
+    /// Every cube stored, no symmetry
+    fn brute_force(active: &HashSet<Point>, dims: usize) -> HashSet<Point> {
+        let mut counts: HashMap<Point, usize> = HashMap::new();
+        for point in active {
+            for k in 0..3usize.pow(dims as u32) {
+                let mut k = k;
+                let n: Point = point
+                    .iter()
+                    .map(|&p| {
+                        let delta = (k % 3) as Position - 1;
+                        k /= 3;
+                        p + delta
+                    })
+                    .collect();
+                if n != *point {
+                    *counts.entry(n).or_default() += 1;
+                }
+            }
+        }
+        counts
+            .into_iter()
+            .filter(|(p, n)| *n == 3 || (*n == 2 && active.contains(p)))
+            .map(|(p, _)| p)
+            .collect()
+    }
+
+    #[test]
+    fn test_example() {
+        let mut pocket = example(3);
+        pocket.run(6);
+        assert_eq!(pocket.active(), 112);
+        let mut pocket = example(4);
+        pocket.run(6);
+        assert_eq!(pocket.active(), 848);
+    }
+
+    #[test]
+    fn test_symmetry() {
+        for dims in 2..=5 {
+            let mut pocket = example(dims);
+            let mut full = pocket.active.clone();
+            for _ in 0..3 {
+                pocket.step();
+                full = brute_force(&full, dims);
+                assert_eq!(pocket.active(), full.len());
+                assert!(full.iter().all(|p| pocket.is_active(p)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_paint() {
+        let mut pocket = example(3);
+        assert_eq!(pocket.paint(0, 1, &[0, 0, 0]), ".#.\n..#\n###\n");
+        pocket.step();
+        assert_eq!(pocket.paint(0, 1, &[0, 0, -1]), "#..\n..#\n.#.\n");
+        assert_eq!(pocket.paint(0, 1, &[0, 0, 0]), "#.#\n.##\n.#.\n");
+        // the x z plane through the middle row of those slices
+        assert_eq!(pocket.paint(0, 2, &[0, 2, 0]), "..#\n.##\n..#\n");
+
+        let mut pocket = example(4);
+        pocket.step();
+        // the middle cube is only active in the starting slice itself
+        assert_eq!(pocket.paint(2, 3, &[1, 2]), "...\n.#.\n...\n");
+        assert_eq!(Pocket::parse("#", 1), Err(ParseError::TooFewDimensions(1)));
+    }
+}
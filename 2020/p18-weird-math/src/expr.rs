@@ -0,0 +1,411 @@
+use std::fmt;
+
+pub type Num = i64;
+
+/// Byte range of the input a token or expression comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    fn join(self, other: Span) -> Span {
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl BinOp {
+    fn from_char(c: char) -> Option<BinOp> {
+        match c {
+            '+' => Some(BinOp::Add),
+            '-' => Some(BinOp::Sub),
+            '*' => Some(BinOp::Mul),
+            '/' => Some(BinOp::Div),
+            _ => None,
+        }
+    }
+
+    fn apply(self, x: Num, y: Num) -> Option<Num> {
+        match self {
+            BinOp::Add => x.checked_add(y),
+            BinOp::Sub => x.checked_sub(y),
+            BinOp::Mul => x.checked_mul(y),
+            BinOp::Div => x.checked_div(y),
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let c = match self {
+            BinOp::Add => '+',
+            BinOp::Sub => '-',
+            BinOp::Mul => '*',
+            BinOp::Div => '/',
+        };
+        write!(f, "{}", c)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assoc {
+    Left,
+    Right,
+}
+
+/// Precedence and associativity of each supported operator. Operators left
+/// out of the table are rejected by the parser.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OperatorTable {
+    ops: [Option<(u8, Assoc)>; 4],
+}
+
+impl OperatorTable {
+    pub fn new() -> OperatorTable {
+        OperatorTable::default()
+    }
+
+    /// Higher precedences bind tighter
+    pub fn with(mut self, op: BinOp, precedence: u8, assoc: Assoc) -> OperatorTable {
+        self.ops[op.index()] = Some((precedence, assoc));
+        self
+    }
+
+    pub fn get(&self, op: BinOp) -> Option<(u8, Assoc)> {
+        self.ops[op.index()]
+    }
+
+    /// Every operator binds the same and evaluates left to right
+    pub fn flat() -> OperatorTable {
+        OperatorTable::new()
+            .with(BinOp::Add, 1, Assoc::Left)
+            .with(BinOp::Sub, 1, Assoc::Left)
+            .with(BinOp::Mul, 1, Assoc::Left)
+            .with(BinOp::Div, 1, Assoc::Left)
+    }
+
+    /// Addition and subtraction bind tighter than multiplication and division
+    pub fn addition_first() -> OperatorTable {
+        OperatorTable::new()
+            .with(BinOp::Add, 2, Assoc::Left)
+            .with(BinOp::Sub, 2, Assoc::Left)
+            .with(BinOp::Mul, 1, Assoc::Left)
+            .with(BinOp::Div, 1, Assoc::Left)
+    }
+
+    /// The usual school precedences
+    pub fn standard() -> OperatorTable {
+        OperatorTable::new()
+            .with(BinOp::Add, 1, Assoc::Left)
+            .with(BinOp::Sub, 1, Assoc::Left)
+            .with(BinOp::Mul, 2, Assoc::Left)
+            .with(BinOp::Div, 2, Assoc::Left)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedChar(char),
+    UnexpectedToken,
+    UnexpectedEnd,
+    UnclosedParen,
+    UnsupportedOperator(BinOp),
+    Overflow,
+    DivisionByZero,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub span: Span,
+}
+
+impl Error {
+    /// The error message followed by the line with the offending part
+    /// underlined
+    pub fn show(&self, line: &str) -> String {
+        let width = line[self.span.start..self.span.end].chars().count().max(1);
+        format!(
+            "{}\n{}\n{}{}",
+            self,
+            line,
+            " ".repeat(line[..self.span.start].chars().count()),
+            "^".repeat(width)
+        )
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c)?,
+            ErrorKind::UnexpectedToken => write!(f, "unexpected token")?,
+            ErrorKind::UnexpectedEnd => write!(f, "unexpected end of expression")?,
+            ErrorKind::UnclosedParen => write!(f, "unclosed parenthesis")?,
+            ErrorKind::UnsupportedOperator(op) => write!(f, "operator {} not supported", op)?,
+            ErrorKind::Overflow => write!(f, "overflow")?,
+            ErrorKind::DivisionByZero => write!(f, "division by zero")?,
+        }
+        write!(f, " at {}..{}", self.span.start, self.span.end)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Num(Num),
+    Op(BinOp),
+    LeftP,
+    RightP,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+pub fn tokenize(line: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = vec![];
+    let mut chars = line.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let mut end = start + c.len_utf8();
+        let kind = match c {
+            ' ' | '\t' => continue,
+            '(' => TokenKind::LeftP,
+            ')' => TokenKind::RightP,
+            c if c.is_ascii_digit() => {
+                while let Some(&(i, d)) = chars.peek() {
+                    if !d.is_ascii_digit() {
+                        break;
+                    }
+                    end = i + 1;
+                    chars.next();
+                }
+                let num = line[start..end].parse().map_err(|_| Error {
+                    kind: ErrorKind::Overflow,
+                    span: Span { start, end },
+                })?;
+                TokenKind::Num(num)
+            }
+            c => match BinOp::from_char(c) {
+                Some(op) => TokenKind::Op(op),
+                None => {
+                    return Err(Error {
+                        kind: ErrorKind::UnexpectedChar(c),
+                        span: Span { start, end },
+                    })
+                }
+            },
+        };
+        tokens.push(Token {
+            kind,
+            span: Span { start, end },
+        });
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Num(Num, Span),
+    Bin(Box<Expr>, BinOp, Box<Expr>, Span),
+}
+
+impl Expr {
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Num(_, span) | Expr::Bin(_, _, _, span) => *span,
+        }
+    }
+
+    fn with_span(self, span: Span) -> Expr {
+        match self {
+            Expr::Num(n, _) => Expr::Num(n, span),
+            Expr::Bin(x, op, y, _) => Expr::Bin(x, op, y, span),
+        }
+    }
+
+    pub fn eval(&self) -> Result<Num, Error> {
+        match self {
+            Expr::Num(n, _) => Ok(*n),
+            Expr::Bin(x, op, y, span) => {
+                let (x, y) = (x.eval()?, y.eval()?);
+                if *op == BinOp::Div && y == 0 {
+                    return Err(Error {
+                        kind: ErrorKind::DivisionByZero,
+                        span: *span,
+                    });
+                }
+                op.apply(x, y).ok_or(Error {
+                    kind: ErrorKind::Overflow,
+                    span: *span,
+                })
+            }
+        }
+    }
+}
+
+/// Fully parenthesized form
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Num(n, _) => write!(f, "{}", n),
+            Expr::Bin(x, op, y, _) => write!(f, "({} {} {})", x, op, y),
+        }
+    }
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    table: &'a OperatorTable,
+    /// where the input ends, for errors about missing tokens
+    end: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn unexpected(&self) -> Error {
+        match self.peek() {
+            Some(token) => Error {
+                kind: ErrorKind::UnexpectedToken,
+                span: token.span,
+            },
+            None => Error {
+                kind: ErrorKind::UnexpectedEnd,
+                span: Span {
+                    start: self.end,
+                    end: self.end,
+                },
+            },
+        }
+    }
+
+    /// a number or a parenthesized expression
+    fn atom(&mut self) -> Result<Expr, Error> {
+        let token = *self.peek().ok_or_else(|| self.unexpected())?;
+        match token.kind {
+            TokenKind::Num(n) => {
+                self.pos += 1;
+                Ok(Expr::Num(n, token.span))
+            }
+            TokenKind::LeftP => {
+                self.pos += 1;
+                let expr = self.expr(0)?;
+                match self.peek() {
+                    Some(&Token {
+                        kind: TokenKind::RightP,
+                        span,
+                    }) => {
+                        self.pos += 1;
+                        Ok(expr.with_span(token.span.join(span)))
+                    }
+                    Some(_) => Err(self.unexpected()),
+                    None => Err(Error {
+                        kind: ErrorKind::UnclosedParen,
+                        span: token.span,
+                    }),
+                }
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    /// precedence climbing: parses operators binding at least as tight as
+    /// `min_precedence`, which is wider than the precedences so that one past
+    /// the highest still fits
+    fn expr(&mut self, min_precedence: u16) -> Result<Expr, Error> {
+        let mut lhs = self.atom()?;
+        while let Some(&Token {
+            kind: TokenKind::Op(op),
+            span,
+        }) = self.peek()
+        {
+            let (precedence, assoc) = self.table.get(op).ok_or(Error {
+                kind: ErrorKind::UnsupportedOperator(op),
+                span,
+            })?;
+            let precedence = u16::from(precedence);
+            if precedence < min_precedence {
+                break;
+            }
+            self.pos += 1;
+            let next = match assoc {
+                Assoc::Left => precedence + 1,
+                Assoc::Right => precedence,
+            };
+            let rhs = self.expr(next)?;
+            let span = lhs.span().join(rhs.span());
+            lhs = Expr::Bin(Box::new(lhs), op, Box::new(rhs), span);
+        }
+        Ok(lhs)
+    }
+}
+
+pub fn parse(line: &str, table: &OperatorTable) -> Result<Expr, Error> {
+    let tokens = tokenize(line)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        table,
+        end: line.len(),
+    };
+    let expr = parser.expr(0)?;
+    if parser.peek().is_some() {
+        return Err(parser.unexpected());
+    }
+    Ok(expr)
+}
+
+pub fn eval(line: &str, table: &OperatorTable) -> Result<Num, Error> {
+    parse(line, table)?.eval()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highest_precedence() {
+        let table = OperatorTable::new()
+            .with(BinOp::Add, u8::MAX, Assoc::Left)
+            .with(BinOp::Mul, 0, Assoc::Left)
+            .with(BinOp::Sub, u8::MAX, Assoc::Right);
+        let expr = parse("1 + 2 + 3 * 4", &table).unwrap();
+        assert_eq!(expr.to_string(), "(((1 + 2) + 3) * 4)");
+        assert_eq!(expr.eval(), Ok(24));
+        assert_eq!(parse("8 - 4 - 2", &table).unwrap().eval(), Ok(6));
+    }
+
+    #[test]
+    fn test_examples() {
+        let line = "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2";
+        assert_eq!(eval(line, &OperatorTable::flat()), Ok(13632));
+        assert_eq!(eval(line, &OperatorTable::addition_first()), Ok(23340));
+        let err = eval("1 + (2 * 3", &OperatorTable::flat()).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::UnclosedParen);
+        assert_eq!(
+            err.show("1 + (2 * 3"),
+            "unclosed parenthesis at 4..5\n1 + (2 * 3\n    ^"
+        );
+    }
+}
@@ -1,30 +1,35 @@
-use crate::rules::{parse::parse, Rule, RuleIdx};
+use p19_regex::rules::earley::{Grammar, GrammarError};
+use p19_regex::rules::{parse::parse, Rule, RuleIdx};
 use std::collections::HashMap;
 use std::fs::read_to_string;
 
-mod rules;
-
 fn main() {
     let filename = std::env::args().nth(1).expect("no path given");
+    let start: RuleIdx = std::env::args()
+        .nth(2)
+        .map(|s| s.parse().expect("bad start rule"))
+        .unwrap_or(0);
     println!("Running with {}", filename);
     let input = read_to_string(filename).unwrap();
     let mut parts = input.split("\n\n");
 
-    let mut rules: HashMap<RuleIdx, Rule> = parts.next().unwrap().lines().map(parse).collect();
-    let rule_zero = rules.remove(&0).unwrap();
-    println!("{}", rule_zero);
+    let rules: HashMap<RuleIdx, Rule> = parts.next().unwrap().lines().map(parse).collect();
+    let grammar = match Grammar::new(&rules) {
+        Ok(grammar) => grammar,
+        Err(e) => return eprintln!("{}", e),
+    };
+    match rules.get(&start) {
+        Some(rule) => println!("{}", rule),
+        None => return eprintln!("{}", GrammarError::UndefinedStart(start)),
+    }
 
-    println!(
-        "NUMBER OF MATCHES: {}",
-        parts
-            .next()
-            .unwrap()
-            .lines()
-            .filter(|l| {
-                let matches = rule_zero.matches_to_end(l, &rules);
-                println!("[{}] = {}", l, matches);
-                matches
-            })
-            .count()
-    );
+    let mut count = 0;
+    for l in parts.next().unwrap_or("").lines() {
+        let matches = grammar.matches(start, l).expect("start rule was checked");
+        println!("[{}] = {}", l, matches);
+        if matches {
+            count += 1;
+        }
+    }
+    println!("NUMBER OF MATCHES: {}", count);
 }
@@ -0,0 +1,421 @@
+use super::{Rule, RuleIdx};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Symbol {
+    Rule(RuleIdx),
+    Char(char),
+}
+
+#[derive(Debug, Clone)]
+struct Production {
+    lhs: RuleIdx,
+    rhs: Vec<Symbol>,
+}
+
+/// A production with a dot marking how much of it has been matched, and the
+/// position in the input where the match started
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Item {
+    prod: usize,
+    dot: usize,
+    origin: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrammarError {
+    UndefinedRule {
+        rule: RuleIdx,
+        used_by: RuleIdx,
+    },
+    /// the rule messages are matched against is not defined
+    UndefinedStart(RuleIdx),
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GrammarError::UndefinedRule { rule, used_by } => {
+                write!(
+                    f,
+                    "rule {} uses rule {}, which is not defined",
+                    used_by, rule
+                )
+            }
+            GrammarError::UndefinedStart(rule) => write!(f, "start rule {} is not defined", rule),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseTree {
+    Leaf(char),
+    Node {
+        rule: RuleIdx,
+        children: Vec<ParseTree>,
+    },
+}
+
+impl ParseTree {
+    /// The part of the message the tree matches
+    pub fn text(&self) -> String {
+        match self {
+            ParseTree::Leaf(c) => c.to_string(),
+            ParseTree::Node { children, .. } => children.iter().map(ParseTree::text).collect(),
+        }
+    }
+}
+
+/// Written as `rule(children...)`
+impl fmt::Display for ParseTree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseTree::Leaf(c) => write!(f, "{}", c),
+            ParseTree::Node { rule, children } => {
+                write!(f, "{}(", rule)?;
+                for (i, child) in children.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}", child)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Rules compiled into an Earley recognizer, so any context free rule set
+/// works, recursive rules included
+#[derive(Debug, Clone)]
+pub struct Grammar {
+    productions: Vec<Production>,
+    by_lhs: HashMap<RuleIdx, Vec<usize>>,
+    nullable: HashSet<RuleIdx>,
+}
+
+/// The Earley sets: items found at each position of the input
+struct Chart {
+    sets: Vec<Vec<Item>>,
+    seen: Vec<HashSet<Item>>,
+}
+
+impl Chart {
+    fn new(len: usize) -> Chart {
+        Chart {
+            sets: vec![vec![]; len + 1],
+            seen: vec![HashSet::new(); len + 1],
+        }
+    }
+
+    fn add(&mut self, pos: usize, item: Item) {
+        if self.seen[pos].insert(item) {
+            self.sets[pos].push(item);
+        }
+    }
+
+    fn contains(&self, pos: usize, item: &Item) -> bool {
+        self.seen[pos].contains(item)
+    }
+}
+
+impl Grammar {
+    pub fn new(rules: &HashMap<RuleIdx, Rule>) -> Result<Grammar, GrammarError> {
+        let mut productions = vec![];
+        let mut idxs = rules.keys().cloned().collect::<Vec<_>>();
+        idxs.sort_unstable();
+        for lhs in idxs {
+            let seqs = match &rules[&lhs] {
+                Rule::Terminal(c) => vec![vec![Symbol::Char(*c)]],
+                Rule::And(seq) => vec![seq.iter().map(|&r| Symbol::Rule(r)).collect()],
+                Rule::Or(seqs) => seqs
+                    .iter()
+                    .map(|seq| seq.iter().map(|&r| Symbol::Rule(r)).collect())
+                    .collect(),
+            };
+            for rhs in seqs {
+                for symbol in &rhs {
+                    if let Symbol::Rule(rule) = symbol {
+                        if !rules.contains_key(rule) {
+                            return Err(GrammarError::UndefinedRule {
+                                rule: *rule,
+                                used_by: lhs,
+                            });
+                        }
+                    }
+                }
+                productions.push(Production { lhs, rhs });
+            }
+        }
+
+        let mut by_lhs: HashMap<RuleIdx, Vec<usize>> = HashMap::new();
+        for (i, p) in productions.iter().enumerate() {
+            by_lhs.entry(p.lhs).or_default().push(i);
+        }
+
+        // rules that can match the empty string
+        let mut nullable = HashSet::new();
+        while {
+            let before = nullable.len();
+            for p in &productions {
+                if p.rhs.iter().all(|s| match s {
+                    Symbol::Rule(r) => nullable.contains(r),
+                    Symbol::Char(_) => false,
+                }) {
+                    nullable.insert(p.lhs);
+                }
+            }
+            nullable.len() != before
+        } {}
+
+        Ok(Grammar {
+            productions,
+            by_lhs,
+            nullable,
+        })
+    }
+
+    fn chart(&self, start: RuleIdx, input: &[char]) -> Chart {
+        let mut chart = Chart::new(input.len());
+        for &prod in self.by_lhs.get(&start).into_iter().flatten() {
+            chart.add(
+                0,
+                Item {
+                    prod,
+                    dot: 0,
+                    origin: 0,
+                },
+            );
+        }
+
+        for pos in 0..=input.len() {
+            let mut i = 0;
+            while i < chart.sets[pos].len() {
+                let item = chart.sets[pos][i];
+                let rhs = &self.productions[item.prod].rhs;
+                match rhs.get(item.dot) {
+                    // predict
+                    Some(Symbol::Rule(rule)) => {
+                        for &prod in &self.by_lhs[rule] {
+                            chart.add(
+                                pos,
+                                Item {
+                                    prod,
+                                    dot: 0,
+                                    origin: pos,
+                                },
+                            );
+                        }
+                        if self.nullable.contains(rule) {
+                            chart.add(
+                                pos,
+                                Item {
+                                    dot: item.dot + 1,
+                                    ..item
+                                },
+                            );
+                        }
+                    }
+                    // scan
+                    Some(Symbol::Char(c)) => {
+                        if input.get(pos) == Some(c) {
+                            chart.add(
+                                pos + 1,
+                                Item {
+                                    dot: item.dot + 1,
+                                    ..item
+                                },
+                            );
+                        }
+                    }
+                    // complete
+                    None => {
+                        let lhs = self.productions[item.prod].lhs;
+                        let mut j = 0;
+                        while j < chart.sets[item.origin].len() {
+                            let waiting = chart.sets[item.origin][j];
+                            let rhs = &self.productions[waiting.prod].rhs;
+                            if rhs.get(waiting.dot) == Some(&Symbol::Rule(lhs)) {
+                                chart.add(
+                                    pos,
+                                    Item {
+                                        dot: waiting.dot + 1,
+                                        ..waiting
+                                    },
+                                );
+                            }
+                            j += 1;
+                        }
+                    }
+                }
+                i += 1;
+            }
+        }
+        chart
+    }
+
+    fn complete(&self, chart: &Chart, rule: RuleIdx, from: usize, to: usize) -> Option<Item> {
+        self.by_lhs.get(&rule)?.iter().find_map(|&prod| {
+            let item = Item {
+                prod,
+                dot: self.productions[prod].rhs.len(),
+                origin: from,
+            };
+            Some(item).filter(|item| chart.contains(to, item))
+        })
+    }
+
+    fn check_start(&self, start: RuleIdx) -> Result<(), GrammarError> {
+        if self.by_lhs.contains_key(&start) {
+            Ok(())
+        } else {
+            Err(GrammarError::UndefinedStart(start))
+        }
+    }
+
+    /// Whether the whole message matches the rule `start`
+    pub fn matches(&self, start: RuleIdx, message: &str) -> Result<bool, GrammarError> {
+        self.check_start(start)?;
+        let input = message.chars().collect::<Vec<_>>();
+        let chart = self.chart(start, &input);
+        Ok(self.complete(&chart, start, 0, input.len()).is_some())
+    }
+
+    pub fn count_matches<'a>(
+        &self,
+        start: RuleIdx,
+        messages: impl IntoIterator<Item = &'a str>,
+    ) -> Result<usize, GrammarError> {
+        self.check_start(start)?;
+        let mut count = 0;
+        for message in messages {
+            if self.matches(start, message)? {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// How the message matches the rule `start`, picking one of the ways if
+    /// the rules are ambiguous. None if it doesn't match.
+    pub fn parse_tree(
+        &self,
+        start: RuleIdx,
+        message: &str,
+    ) -> Result<Option<ParseTree>, GrammarError> {
+        self.check_start(start)?;
+        let input = message.chars().collect::<Vec<_>>();
+        let chart = self.chart(start, &input);
+        Ok(self
+            .complete(&chart, start, 0, input.len())
+            .map(|item| self.build(&chart, item, input.len())))
+    }
+
+    /// Rebuilds the tree of a completed item ending at `end`, matching its
+    /// symbols right to left against the items in the chart
+    fn build(&self, chart: &Chart, item: Item, end: usize) -> ParseTree {
+        let production = &self.productions[item.prod];
+        let mut children = vec![];
+        let mut pos = end;
+        for (k, symbol) in production.rhs.iter().enumerate().rev() {
+            // the part of the production before this symbol
+            let prefix = |at: usize| {
+                chart.contains(
+                    at,
+                    &Item {
+                        prod: item.prod,
+                        dot: k,
+                        origin: item.origin,
+                    },
+                )
+            };
+            match symbol {
+                Symbol::Char(c) => {
+                    children.push(ParseTree::Leaf(*c));
+                    pos -= 1;
+                }
+                Symbol::Rule(rule) => {
+                    let (child, from) = (item.origin..=pos)
+                        .rev()
+                        .filter(|&from| prefix(from))
+                        .find_map(|from| {
+                            self.complete(chart, *rule, from, pos)
+                                .map(|child| (child, from))
+                        })
+                        .expect("completed items come from the chart");
+                    children.push(self.build(chart, child, pos));
+                    pos = from;
+                }
+            }
+        }
+        children.reverse();
+        ParseTree::Node {
+            rule: production.lhs,
+            children,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::parse::parse;
+
+    fn grammar(path: &str) -> (Grammar, Vec<String>) {
+        let input = std::fs::read_to_string(path).unwrap();
+        let mut parts = input.split("\n\n");
+        let rules: HashMap<RuleIdx, Rule> = parts.next().unwrap().lines().map(parse).collect();
+        let messages = parts.next().unwrap().lines().map(String::from).collect();
+        (Grammar::new(&rules).unwrap(), messages)
+    }
+
+    #[test]
+    fn test_parse_tree() {
+        let (grammar, messages) = grammar("data/testB_1.txt");
+        assert_eq!(
+            grammar.count_matches(0, messages.iter().map(String::as_str)),
+            Ok(12)
+        );
+
+        // 0: 8 11, where 8 repeats 42 and 11 nests 42 ... 31
+        let message = "babbbbaabbbbbabbbbbbaabaaabaaa";
+        let tree = grammar.parse_tree(0, message).unwrap().unwrap();
+        assert_eq!(tree.text(), message);
+        let children = match &tree {
+            ParseTree::Node { rule: 0, children } => children,
+            _ => panic!("root should be rule 0, got {}", tree),
+        };
+        let rules: Vec<_> = children
+            .iter()
+            .map(|c| match c {
+                ParseTree::Node { rule, .. } => *rule,
+                ParseTree::Leaf(_) => panic!("rule 0 has no characters"),
+            })
+            .collect();
+        assert_eq!(rules, vec![8, 11]);
+
+        assert_eq!(grammar.parse_tree(0, "aaaabbb"), Ok(None));
+        assert_eq!(
+            grammar.parse_tree(1, "a").unwrap().unwrap().to_string(),
+            "1(a)"
+        );
+    }
+
+    #[test]
+    fn test_undefined_start() {
+        let (grammar, _) = grammar("data/testB_1.txt");
+        assert_eq!(
+            grammar.matches(99, "a"),
+            Err(GrammarError::UndefinedStart(99))
+        );
+        assert_eq!(
+            grammar.parse_tree(99, "a"),
+            Err(GrammarError::UndefinedStart(99))
+        );
+        assert_eq!(
+            grammar.count_matches(99, vec![]),
+            Err(GrammarError::UndefinedStart(99))
+        );
+    }
+}
@@ -12,7 +12,7 @@ enum State {
 pub fn parse(line: &str) -> (RuleIdx, Rule) {
     let mut state = State::Start;
     let mut idx = None;
-    for part in line.split(|c| c == '|' || c == ':').map(|part| part.trim()) {
+    for part in line.split(['|', ':']).map(|part| part.trim()) {
         match state {
             State::Start => {
                 idx = part.parse().ok();
@@ -20,7 +20,6 @@ pub fn parse(line: &str) -> (RuleIdx, Rule) {
             }
             State::IdxParsed => {
                 if let Ok(vec) = part
-                    .trim()
                     .split_whitespace()
                     .map(|n| n.parse::<RuleIdx>())
                     .collect()
@@ -34,7 +33,6 @@ pub fn parse(line: &str) -> (RuleIdx, Rule) {
             }
             State::VecParsed(v) => {
                 let vec = part
-                    .trim()
                     .split_whitespace()
                     .map(|n| n.parse::<RuleIdx>().unwrap())
                     .collect();
@@ -43,13 +41,12 @@ pub fn parse(line: &str) -> (RuleIdx, Rule) {
             }
             State::OrParsed(mut vs) => {
                 let vec = part
-                    .trim()
                     .split_whitespace()
                     .map(|n| n.parse::<RuleIdx>().unwrap())
                     .collect();
                 vs.push(vec);
                 state = State::OrParsed(vs);
-            },
+            }
             State::TerminalParsed { .. } => {}
         }
     }
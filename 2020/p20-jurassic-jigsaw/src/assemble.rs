@@ -0,0 +1,239 @@
+use crate::border::{Border, BorderIndex};
+use crate::grid::Grid;
+use crate::op::OPERATIONS;
+use crate::TileId;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssemblyError {
+    Empty,
+    /// every tile has a neighbour on at least three sides
+    NoCorner,
+    /// nothing fits at this spot of the puzzle
+    NoFit {
+        row: usize,
+        col: usize,
+    },
+    /// tiles that did not make it into the rectangle
+    Leftover(Vec<TileId>),
+}
+
+impl fmt::Display for AssemblyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssemblyError::Empty => write!(f, "no tiles"),
+            AssemblyError::NoCorner => write!(f, "no tile can go in a corner"),
+            AssemblyError::NoFit { row, col } => {
+                write!(f, "no tile fits at row {} column {}", row, col)
+            }
+            AssemblyError::Leftover(ids) => write!(f, "tiles left out: {:?}", ids),
+        }
+    }
+}
+
+/// Oriented tiles in row major order
+#[derive(Debug, Clone)]
+pub struct Puzzle {
+    pub rows: usize,
+    pub cols: usize,
+    pub placed: Vec<(TileId, Grid)>,
+}
+
+impl Puzzle {
+    pub fn at(&self, row: usize, col: usize) -> &(TileId, Grid) {
+        &self.placed[row * self.cols + col]
+    }
+
+    pub fn corners(&self) -> Vec<TileId> {
+        let mut corners = vec![
+            self.at(0, 0).0,
+            self.at(0, self.cols - 1).0,
+            self.at(self.rows - 1, 0).0,
+            self.at(self.rows - 1, self.cols - 1).0,
+        ];
+        corners.sort_unstable();
+        corners.dedup();
+        corners
+    }
+
+    /// The tiles put together without their borders
+    pub fn image(&self) -> Grid {
+        let widths: Vec<usize> = (0..self.cols)
+            .map(|c| self.at(0, c).1.interior().width())
+            .collect();
+        let heights: Vec<usize> = (0..self.rows)
+            .map(|r| self.at(r, 0).1.interior().height())
+            .collect();
+        let mut image = Grid::new(widths.iter().sum(), heights.iter().sum());
+        let mut y = 0;
+        for (r, height) in heights.iter().enumerate() {
+            let mut x = 0;
+            for (c, width) in widths.iter().enumerate() {
+                image.paste(&self.at(r, c).1.interior(), (x, y));
+                x += width;
+            }
+            y += height;
+        }
+        image
+    }
+}
+
+/// Puts the tiles together starting from a corner and going row by row. Each
+/// spot only considers the tiles sharing an edge with its already placed
+/// neighbours, so every edge is expected to fit at most one other tile.
+pub fn assemble(tiles: &HashMap<TileId, Grid>) -> Result<Puzzle, AssemblyError> {
+    if tiles.is_empty() {
+        return Err(AssemblyError::Empty);
+    }
+    let index = BorderIndex::new(tiles);
+    let mut used = HashSet::new();
+    let unmatched = |id: TileId, tile: &Grid, border: Border, used: &HashSet<TileId>| {
+        index
+            .neighbours(id, &tile.edge(border))
+            .all(|other| used.contains(&other))
+    };
+
+    let corner = *index
+        .corners(tiles)
+        .first()
+        .ok_or(AssemblyError::NoCorner)?;
+    let first = OPERATIONS
+        .iter()
+        .map(|&op| tiles[&corner].transformed(op))
+        .find(|t| unmatched(corner, t, Border::U, &used) && unmatched(corner, t, Border::L, &used))
+        .ok_or(AssemblyError::NoCorner)?;
+    used.insert(corner);
+
+    let mut placed: Vec<(TileId, Grid)> = vec![(corner, first)];
+    let mut cols: Option<usize> = None;
+    let mut row = 0;
+    loop {
+        let mut col = 1;
+        loop {
+            let (left_id, left) = &placed[placed.len() - 1];
+            let above = cols.map(|cols| &placed[placed.len() - cols].1);
+            if cols == Some(col) || unmatched(*left_id, left, Border::R, &used) {
+                break;
+            }
+            let next = fit(tiles, &index, &used, *left_id, left, Border::R, above)
+                .ok_or(AssemblyError::NoFit { row, col })?;
+            used.insert(next.0);
+            placed.push(next);
+            col += 1;
+        }
+        match cols {
+            None => cols = Some(col),
+            Some(cols) if cols != col => return Err(AssemblyError::NoFit { row, col }),
+            _ => {}
+        }
+        let cols = cols.unwrap();
+
+        let (above_id, above) = &placed[row * cols];
+        if unmatched(*above_id, above, Border::D, &used) {
+            break;
+        }
+        row += 1;
+        let next = fit(tiles, &index, &used, *above_id, above, Border::D, None)
+            .ok_or(AssemblyError::NoFit { row, col: 0 })?;
+        used.insert(next.0);
+        placed.push(next);
+    }
+
+    let mut leftover: Vec<TileId> = tiles
+        .keys()
+        .filter(|id| !used.contains(id))
+        .cloned()
+        .collect();
+    if !leftover.is_empty() {
+        leftover.sort_unstable();
+        return Err(AssemblyError::Leftover(leftover));
+    }
+    Ok(Puzzle {
+        rows: row + 1,
+        cols: cols.unwrap(),
+        placed,
+    })
+}
+
+/// Finds an unused tile and orientation that fits on the `side` of `from`,
+/// and below `above` if given
+fn fit(
+    tiles: &HashMap<TileId, Grid>,
+    index: &BorderIndex,
+    used: &HashSet<TileId>,
+    from_id: TileId,
+    from: &Grid,
+    side: Border,
+    above: Option<&Grid>,
+) -> Option<(TileId, Grid)> {
+    let edge = from.edge(side);
+    index
+        .neighbours(from_id, &edge)
+        .filter(|id| !used.contains(id))
+        .find_map(|id| {
+            OPERATIONS
+                .iter()
+                .map(|&op| tiles[&id].transformed(op))
+                .find(|t| {
+                    t.edge(side.oposite()) == edge
+                        && above.is_none_or(|above| t.edge(Border::U) == above.edge(Border::D))
+                })
+                .map(|t| (id, t))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse_tiles;
+
+    fn tiles(path: &str) -> HashMap<TileId, Grid> {
+        parse_tiles(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    fn only(tiles: HashMap<TileId, Grid>, ids: &[TileId]) -> HashMap<TileId, Grid> {
+        tiles
+            .into_iter()
+            .filter(|(id, _)| ids.contains(id))
+            .collect()
+    }
+
+    #[test]
+    fn test_strip() {
+        // the middle row of the example, where the middle tile has the lowest id
+        let tiles = only(tiles("data/test1.txt"), &[2729, 1427, 2473]);
+        let puzzle = assemble(&tiles).unwrap();
+        assert_eq!(puzzle.rows * puzzle.cols, 3);
+        assert_eq!(puzzle.rows.min(puzzle.cols), 1);
+        assert_eq!(puzzle.corners(), vec![2473, 2729]);
+        assert_eq!(puzzle.at(0, 1).0, 1427);
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let tiles = tiles("data/rectangle.txt");
+        let puzzle = assemble(&tiles).unwrap();
+        assert_eq!(puzzle.rows * puzzle.cols, 6);
+        assert_eq!(puzzle.rows.min(puzzle.cols), 2);
+        assert_eq!(puzzle.corners(), vec![1171, 1489, 2311, 3079]);
+        let image = puzzle.image();
+        assert_eq!(image.width() * image.height(), 8 * 8 * 6);
+    }
+
+    #[test]
+    fn test_example() {
+        let tiles = tiles("data/test1.txt");
+        let puzzle = assemble(&tiles).unwrap();
+        assert_eq!((puzzle.rows, puzzle.cols), (3, 3));
+        assert_eq!(puzzle.corners().iter().product::<TileId>(), 20899048083289);
+    }
+
+    #[test]
+    fn test_staircase() {
+        assert_eq!(
+            assemble(&tiles("data/test2.txt")).unwrap_err(),
+            AssemblyError::Leftover(vec![1951, 2311])
+        );
+    }
+}
@@ -1,6 +1,8 @@
-use crate::Tile;
+use crate::grid::Grid;
+use crate::TileId;
+use std::collections::HashMap;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Border {
     U,
     D,
@@ -10,28 +12,6 @@ pub enum Border {
 pub const BORDERS: [Border; 4] = [Border::R, Border::L, Border::U, Border::D];
 
 impl Border {
-    #[allow(clippy::ptr_arg)]
-    pub fn get(&self, tile: &Tile) -> Vec<usize> {
-        let max_x = tile.iter().map(|p| p.0).max().unwrap();
-        let max_y = tile.iter().map(|p| p.1).max().unwrap();
-        let mut border: Vec<usize> = tile
-            .iter()
-            .filter(|(x, y)| match self {
-                Border::U => *y == 0,
-                Border::D => *y == max_y,
-                Border::L => *x == 0,
-                Border::R => *x == max_x,
-            })
-            .map(|(x, y)| match self {
-                Border::U | Border::D => x,
-                Border::L | Border::R => y,
-            })
-            .cloned()
-            .collect();
-        border.sort_unstable();
-        border
-    }
-
     pub fn oposite(&self) -> Self {
         match self {
             Border::U => Border::D,
@@ -40,13 +20,76 @@ impl Border {
             Border::L => Border::R,
         }
     }
+}
 
-    pub fn position_at_border(&self, placement: &(usize, usize)) -> (usize, usize) {
-        match self {
-            Border::U => (placement.0, placement.1 - 1),
-            Border::D => (placement.0, placement.1 + 1),
-            Border::R => (placement.0 + 1, placement.1),
-            Border::L => (placement.0 - 1, placement.1),
+/// An edge read in whichever direction comes first, so it is the same for
+/// every orientation of the tile
+pub type Signature = Vec<bool>;
+
+pub fn signature(edge: &[bool]) -> Signature {
+    let reversed: Vec<bool> = edge.iter().rev().cloned().collect();
+    if reversed.as_slice() < edge {
+        reversed
+    } else {
+        edge.to_vec()
+    }
+}
+
+/// Which tiles have each edge signature
+#[derive(Debug, Clone, Default)]
+pub struct BorderIndex {
+    by_signature: HashMap<Signature, Vec<TileId>>,
+}
+
+impl BorderIndex {
+    pub fn new(tiles: &HashMap<TileId, Grid>) -> BorderIndex {
+        let mut by_signature: HashMap<Signature, Vec<TileId>> = HashMap::new();
+        for (&id, tile) in tiles {
+            for &border in &BORDERS {
+                by_signature
+                    .entry(signature(&tile.edge(border)))
+                    .or_default()
+                    .push(id);
+            }
         }
+        BorderIndex { by_signature }
+    }
+
+    /// Other tiles with a border that fits `edge`
+    pub fn neighbours(&self, id: TileId, edge: &[bool]) -> impl Iterator<Item = TileId> + '_ {
+        self.by_signature
+            .get(&signature(edge))
+            .into_iter()
+            .flatten()
+            .cloned()
+            .filter(move |&other| other != id)
+    }
+
+    /// Borders of the tile no other tile fits
+    pub fn unmatched(&self, id: TileId, tile: &Grid) -> Vec<Border> {
+        BORDERS
+            .iter()
+            .cloned()
+            .filter(|&border| self.neighbours(id, &tile.edge(border)).next().is_none())
+            .collect()
+    }
+
+    /// Tiles with two adjacent unmatched borders, sorted. The middle tiles
+    /// of a single row have two unmatched borders too, but opposite ones.
+    pub fn corners(&self, tiles: &HashMap<TileId, Grid>) -> Vec<TileId> {
+        let mut corners: Vec<TileId> = tiles
+            .iter()
+            .filter(|(&id, tile)| {
+                let unmatched = self.unmatched(id, tile);
+                unmatched.iter().any(|b| {
+                    unmatched
+                        .iter()
+                        .any(|&other| other != *b && other != b.oposite())
+                })
+            })
+            .map(|(&id, _)| id)
+            .collect();
+        corners.sort_unstable();
+        corners
     }
 }
@@ -0,0 +1,117 @@
+use crate::border::Border;
+use crate::op::Op;
+use crate::Position;
+use std::fmt;
+
+/// A rectangle of pixels, `#` being on and `.` off
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    cells: Vec<bool>,
+}
+
+impl Grid {
+    pub fn new(width: usize, height: usize) -> Grid {
+        Grid {
+            width,
+            height,
+            cells: vec![false; width * height],
+        }
+    }
+
+    /// Parses rows of `#` and `.`, any other character counts as off. Rows
+    /// shorter than the longest one are padded.
+    pub fn parse(rows: &str) -> Grid {
+        let rows: Vec<&str> = rows.lines().collect();
+        let width = rows.iter().map(|r| r.chars().count()).max().unwrap_or(0);
+        let mut grid = Grid::new(width, rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                grid.set((x, y), c == '#');
+            }
+        }
+        grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): Position) -> bool {
+        x < self.width && y < self.height && self.cells[x + y * self.width]
+    }
+
+    pub fn set(&mut self, (x, y): Position, on: bool) {
+        self.cells[x + y * self.width] = on;
+    }
+
+    /// Positions of the pixels that are on
+    pub fn on(&self) -> impl Iterator<Item = Position> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &on)| on)
+            .map(move |(i, _)| (i % self.width, i / self.width))
+    }
+
+    pub fn count_on(&self) -> usize {
+        self.cells.iter().filter(|&&on| on).count()
+    }
+
+    pub fn transformed(&self, op: Op) -> Grid {
+        let (width, height) = if op.transposes() {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        };
+        let mut grid = Grid::new(width, height);
+        for pos in self.on() {
+            grid.set(op.apply(pos, self.width, self.height), true);
+        }
+        grid
+    }
+
+    /// Pixels of one side, read left to right or top to bottom
+    pub fn edge(&self, border: Border) -> Vec<bool> {
+        let (w, h) = (self.width, self.height);
+        match border {
+            Border::U => (0..w).map(|x| self.get((x, 0))).collect(),
+            Border::D => (0..w).map(|x| self.get((x, h - 1))).collect(),
+            Border::L => (0..h).map(|y| self.get((0, y))).collect(),
+            Border::R => (0..h).map(|y| self.get((w - 1, y))).collect(),
+        }
+    }
+
+    /// The grid without its outermost rows and columns
+    pub fn interior(&self) -> Grid {
+        let mut grid = Grid::new(self.width.saturating_sub(2), self.height.saturating_sub(2));
+        for (x, y) in self.on() {
+            if 0 < x && x <= grid.width && 0 < y && y <= grid.height {
+                grid.set((x - 1, y - 1), true);
+            }
+        }
+        grid
+    }
+
+    /// Copies `other` with its top left corner at `at`
+    pub fn paste(&mut self, other: &Grid, (ax, ay): Position) {
+        for (x, y) in other.on() {
+            self.set((ax + x, ay + y), true);
+        }
+    }
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.cells.chunks(self.width.max(1)) {
+            let row: String = row.iter().map(|&on| if on { '#' } else { '.' }).collect();
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
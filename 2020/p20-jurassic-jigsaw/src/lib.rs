@@ -0,0 +1,10 @@
+pub mod assemble;
+pub mod border;
+pub mod grid;
+pub mod op;
+pub mod paint;
+pub mod parse;
+pub mod pattern;
+
+pub type TileId = usize;
+pub type Position = (usize, usize);
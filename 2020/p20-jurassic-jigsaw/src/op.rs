@@ -1,101 +1,117 @@
-use crate::Tile;
+/// An element of the dihedral group D4, the 8 symmetries of a square: rotate
+/// clockwise `rotate` quarter turns and then, if `flip`, mirror left to right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Op {
+    pub rotate: u8,
+    pub flip: bool,
+}
+
+pub const IDENTITY: Op = Op {
+    rotate: 0,
+    flip: false,
+};
 
 pub const OPERATIONS: [Op; 8] = [
+    IDENTITY,
     Op {
-        rotate: None,
-        flip: None,
+        rotate: 1,
+        flip: false,
     },
     Op {
-        rotate: Some(Rotate::One),
-        flip: None,
+        rotate: 2,
+        flip: false,
     },
     Op {
-        rotate: Some(Rotate::Two),
-        flip: None,
+        rotate: 3,
+        flip: false,
     },
     Op {
-        rotate: Some(Rotate::Three),
-        flip: None,
+        rotate: 0,
+        flip: true,
     },
     Op {
-        rotate: None,
-        flip: Some(Flip),
+        rotate: 1,
+        flip: true,
     },
     Op {
-        rotate: Some(Rotate::One),
-        flip: Some(Flip),
+        rotate: 2,
+        flip: true,
     },
     Op {
-        rotate: Some(Rotate::Two),
-        flip: Some(Flip),
-    },
-    Op {
-        rotate: Some(Rotate::Three),
-        flip: Some(Flip),
+        rotate: 3,
+        flip: true,
     },
 ];
 
-#[derive(Debug)]
-pub struct Flip;
-#[derive(Debug)]
-pub enum Rotate {
-    One,
-    Two,
-    Three,
-}
-
-#[derive(Debug)]
-pub struct Op {
-    pub rotate: Option<Rotate>,
-    pub flip: Option<Flip>,
-}
-
-pub trait Operation {
-    #[allow(clippy::ptr_arg)]
-    fn operate_clone(&self, tile: &Tile) -> Tile {
-        let mut tile = tile.to_owned();
-        self.operate(&mut tile);
-        tile
+impl Op {
+    /// `self.then(other)` applies `self` first and `other` afterwards.
+    ///
+    /// Mirroring turns clockwise rotations into counterclockwise ones, so
+    /// moving `self`'s flip past `other`'s rotation negates the rotation.
+    pub fn then(self, other: Op) -> Op {
+        let rotate = if self.flip {
+            4 - other.rotate % 4
+        } else {
+            other.rotate
+        };
+        Op {
+            rotate: (self.rotate + rotate) % 4,
+            flip: self.flip != other.flip,
+        }
     }
-    fn operate(&self, tile: &mut Tile);
-}
 
-impl Operation for Flip {
-    fn operate(&self, tile: &mut Tile) {
-        let width = *tile.iter().map(|(x, _y)| x).max().unwrap();
-        for (x, _) in tile.iter_mut() {
-            *x = width - *x;
+    pub fn inverse(self) -> Op {
+        let rotate = if self.flip {
+            self.rotate
+        } else {
+            (4 - self.rotate) % 4
+        };
+        Op {
+            rotate,
+            flip: self.flip,
         }
     }
-}
 
-impl Operation for Rotate {
-    fn operate(&self, tile: &mut Tile) {
-        let times = match self {
-            Rotate::One => 1,
-            Rotate::Two => 2,
-            Rotate::Three => 3,
-        };
-        for _ in 0..times {
-            // rotate is transpose and then horizontal flip
+    /// Whether width and height trade places
+    pub fn transposes(self) -> bool {
+        self.rotate % 2 == 1
+    }
 
-            // transpose:
-            for pos in tile.iter_mut() {
-                *pos = (pos.1, pos.0);
-            }
-            // and flip
-            Operation::operate(&Flip, tile);
+    /// Where the cell at `(x, y)` of a `width` x `height` grid ends up
+    pub fn apply(self, (x, y): (usize, usize), width: usize, height: usize) -> (usize, usize) {
+        let (mut x, mut y, mut w, mut h) = (x, y, width, height);
+        for _ in 0..self.rotate % 4 {
+            // rotating clockwise is transposing and then mirroring
+            let (nx, ny) = (h - 1 - y, x);
+            x = nx;
+            y = ny;
+            std::mem::swap(&mut w, &mut h);
+        }
+        if self.flip {
+            x = w - 1 - x;
         }
+        (x, y)
     }
 }
 
-impl Operation for Op {
-    fn operate(&self, tile: &mut Tile) {
-        if let Some(ref rotate) = self.rotate {
-            rotate.operate(tile);
-        }
-        if let Some(ref flip) = self.flip {
-            flip.operate(tile);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    #[test]
+    fn test_group_laws() {
+        // not square, so transposing operations show
+        let grid = Grid::parse("##.\n#..\n...\n.#.\n");
+        for &a in &OPERATIONS {
+            assert_eq!(a.then(a.inverse()), IDENTITY);
+            assert_eq!(grid.transformed(a).transformed(a.inverse()), grid);
+            for &b in &OPERATIONS {
+                assert_eq!(
+                    grid.transformed(a).transformed(b),
+                    grid.transformed(a.then(b))
+                );
+            }
         }
     }
 }
@@ -1,48 +1,20 @@
-use crate::Tile;
+use crate::grid::Grid;
+use crate::Position;
 use std::collections::HashSet;
 
-#[allow(clippy::ptr_arg)]
-pub fn paint_tile(tile: &Tile) {
-    let (max_x, max_y) = tile
-        .iter()
-        .fold((0_usize, 0_usize), |(max_x, max_y), (x, y)| {
-            (max_x.max(*x), max_y.max(*y))
-        });
-
-    let mut paint = String::with_capacity(max_y * max_x);
-    for y in 0..=max_y {
-        for x in 0..=max_x {
-            if tile.contains(&(x, y)) {
-                paint.push('█');
-                paint.push('█');
-            } else {
-                paint.push(' ');
-                paint.push(' ');
-            }
-        }
-        paint.push('\n');
-    }
-
-    println!("{}", paint);
+pub fn paint_tile(tile: &Grid) {
+    let on: HashSet<Position> = tile.on().collect();
+    paint_tile_with_monsters(tile, &on)
 }
 
-pub fn paint_tile_with_monsters(
-    tile: &HashSet<(usize, usize)>,
-    tiles_with_monsters: &HashSet<(usize, usize)>,
-) {
-    let (max_x, max_y) = tile
-        .iter()
-        .fold((0_usize, 0_usize), |(max_x, max_y), (x, y)| {
-            (max_x.max(*x), max_y.max(*y))
-        });
-
-    let mut paint = String::with_capacity(max_y * max_x);
-    for y in 0..=max_y {
-        for x in 0..=max_x {
+pub fn paint_tile_with_monsters(tile: &Grid, tiles_with_monsters: &HashSet<Position>) {
+    let mut paint = String::with_capacity(tile.height() * (tile.width() * 2 + 1));
+    for y in 0..tile.height() {
+        for x in 0..tile.width() {
             if tiles_with_monsters.contains(&(x, y)) {
                 paint.push('█');
                 paint.push('█');
-            } else if tile.contains(&(x, y)) {
+            } else if tile.get((x, y)) {
                 paint.push('~');
                 paint.push('~');
             } else {
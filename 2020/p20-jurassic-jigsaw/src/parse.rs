@@ -1,32 +1,76 @@
-use crate::{Tile, TileId};
+use crate::grid::Grid;
+use crate::TileId;
 use std::collections::HashMap;
-use std::fs::read_to_string;
-
-pub fn parse_tile(data: &str) -> (TileId, Tile) {
-    let mut lines = data.lines();
-    let tile_id: TileId = lines
-        .next()
-        .expect("tile has a tile id line")
-        .split(|c| c == ' ' || c == ':')
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    BadHeader(String),
+    /// a tile without any pixel rows
+    Empty(TileId),
+    DuplicateTile(TileId),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::BadHeader(line) => write!(f, "bad tile header {:?}", line),
+            ParseError::Empty(id) => write!(f, "tile {} is empty", id),
+            ParseError::DuplicateTile(id) => write!(f, "tile {} shows up twice", id),
+        }
+    }
+}
+
+pub fn parse_tile(data: &str) -> Result<(TileId, Grid), ParseError> {
+    let (header, rows) = data.split_once('\n').unwrap_or((data, ""));
+    let tile_id: TileId = header
+        .split([' ', ':'])
         .nth(1)
-        .expect("tile id line has id")
-        .parse()
-        .unwrap();
-    let tiles = lines
-        .enumerate()
-        .flat_map(|(y, l)| {
-            l.char_indices()
-                .filter_map(move |(x, c)| if c == '#' { Some((x, y)) } else { None })
-        })
-        .collect();
-
-    (tile_id, tiles)
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| ParseError::BadHeader(header.to_string()))?;
+
+    let grid = Grid::parse(rows);
+    if grid.width() == 0 || grid.height() == 0 {
+        return Err(ParseError::Empty(tile_id));
+    }
+    Ok((tile_id, grid))
+}
+
+/// Tiles separated by blank lines
+pub fn parse_tiles(input: &str) -> Result<HashMap<TileId, Grid>, ParseError> {
+    let mut tiles = HashMap::new();
+    for part in input.split("\n\n").filter(|part| !part.trim().is_empty()) {
+        let (id, grid) = parse_tile(part)?;
+        if tiles.insert(id, grid).is_some() {
+            return Err(ParseError::DuplicateTile(id));
+        }
+    }
+    Ok(tiles)
 }
 
-pub fn parse_tile_file(path: &str) -> HashMap<TileId, Tile> {
-    read_to_string(path)
-        .expect("bad file")
-        .split("\n\n")
-        .map(|part| parse_tile(part))
-        .collect()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_tile() {
+        assert_eq!(parse_tile("Tile 7:"), Err(ParseError::Empty(7)));
+        assert_eq!(parse_tile("Tile 7:\n"), Err(ParseError::Empty(7)));
+        assert!(matches!(
+            parse_tile("Tile x:\n#."),
+            Err(ParseError::BadHeader(_))
+        ));
+        let (id, grid) = parse_tile("Tile 7:\n#.\n.#").unwrap();
+        assert_eq!((id, grid.width(), grid.height()), (7, 2, 2));
+    }
+
+    #[test]
+    fn test_tiles() {
+        let tiles = parse_tiles("Tile 1:\n#.\n.#\n\nTile 2:\n..\n..\n").unwrap();
+        assert_eq!(tiles.len(), 2);
+        assert_eq!(
+            parse_tiles("Tile 1:\n#.\n.#\n\nTile 1:\n..\n.."),
+            Err(ParseError::DuplicateTile(1))
+        );
+    }
 }
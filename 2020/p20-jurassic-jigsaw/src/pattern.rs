@@ -0,0 +1,81 @@
+use crate::grid::Grid;
+use crate::op::{Op, OPERATIONS};
+use crate::Position;
+use std::collections::HashSet;
+use std::fs::read_to_string;
+use std::io;
+
+/// A shape to look for in an image, like the sea monster. Only the `#` of
+/// the stencil must match, anything else is ignored.
+#[derive(Debug, Clone)]
+pub struct Stencil {
+    cells: Vec<Position>,
+    width: usize,
+    height: usize,
+}
+
+/// The orientation of the image where the stencil shows up, and the pixels
+/// it covers there
+#[derive(Debug, Clone)]
+pub struct Sighting {
+    pub op: Op,
+    pub image: Grid,
+    pub cells: HashSet<Position>,
+}
+
+impl Sighting {
+    /// Pixels that are on but not part of any match
+    pub fn roughness(&self) -> usize {
+        self.image.count_on() - self.cells.len()
+    }
+}
+
+impl Stencil {
+    pub fn parse(rows: &str) -> Stencil {
+        let grid = Grid::parse(rows);
+        let cells: Vec<Position> = grid.on().collect();
+        Stencil {
+            width: cells.iter().map(|p| p.0 + 1).max().unwrap_or(0),
+            height: cells.iter().map(|p| p.1 + 1).max().unwrap_or(0),
+            cells,
+        }
+    }
+
+    pub fn from_path(path: &str) -> io::Result<Stencil> {
+        Ok(Stencil::parse(&read_to_string(path)?))
+    }
+
+    pub fn cells(&self) -> &[Position] {
+        &self.cells
+    }
+
+    /// Pixels covered by every match of the stencil, which may overlap
+    pub fn matches(&self, image: &Grid) -> HashSet<Position> {
+        let mut found = HashSet::new();
+        if self.cells.is_empty() || self.width > image.width() || self.height > image.height() {
+            return found;
+        }
+        for dy in 0..=image.height() - self.height {
+            for dx in 0..=image.width() - self.width {
+                let shifted = self.cells.iter().map(|&(x, y)| (x + dx, y + dy));
+                if shifted.clone().all(|pos| image.get(pos)) {
+                    found.extend(shifted);
+                }
+            }
+        }
+        found
+    }
+
+    /// Tries every orientation of the image until the stencil matches
+    pub fn find(&self, image: &Grid) -> Option<Sighting> {
+        OPERATIONS.iter().find_map(|&op| {
+            let image = image.transformed(op);
+            let cells = self.matches(&image);
+            if cells.is_empty() {
+                None
+            } else {
+                Some(Sighting { op, image, cells })
+            }
+        })
+    }
+}
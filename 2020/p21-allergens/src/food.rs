@@ -0,0 +1,106 @@
+use std::fmt;
+
+/// One line of the list: some ingredients and some of the allergens in them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Food {
+    pub ingredients: Vec<String>,
+    pub allergens: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1 based
+    pub line: usize,
+    pub content: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: expected `ingredients (contains allergens)`, found {:?}",
+            self.line, self.content
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FoodList {
+    pub foods: Vec<Food>,
+}
+
+impl FoodList {
+    /// One food per line, like `mxmxvkd kfcds (contains dairy, fish)`. The
+    /// allergens may be left out when none is known.
+    pub fn parse(input: &str) -> Result<FoodList, ParseError> {
+        let foods = input
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(line, l)| {
+                let bad = || ParseError {
+                    line: line + 1,
+                    content: l.to_string(),
+                };
+                let l = l.trim();
+                let (ingredients, allergens) = match l.split_once(" (contains ") {
+                    Some((ingredients, allergens)) => {
+                        let allergens = allergens.strip_suffix(')').ok_or_else(bad)?;
+                        let allergens: Vec<_> =
+                            allergens.split(',').map(|a| a.trim().to_string()).collect();
+                        if allergens.iter().any(|a| a.is_empty() || a.contains(' ')) {
+                            return Err(bad());
+                        }
+                        (ingredients, allergens)
+                    }
+                    None => (l, Vec::new()),
+                };
+                if ingredients.contains(['(', ')']) {
+                    return Err(bad());
+                }
+                Ok(Food {
+                    ingredients: ingredients.split_whitespace().map(String::from).collect(),
+                    allergens,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(FoodList { foods })
+    }
+
+    /// How many times the ingredient shows up in the list
+    pub fn appearances(&self, ingredient: &str) -> usize {
+        self.foods
+            .iter()
+            .filter(|f| f.ingredients.iter().any(|i| i == ingredient))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let list = FoodList::parse(&std::fs::read_to_string("data/test1.txt").unwrap()).unwrap();
+        assert_eq!(list.foods.len(), 4);
+        assert_eq!(
+            list.foods[0],
+            Food {
+                ingredients: vec!["mxmxvkd", "kfcds", "sqjhc", "nhms"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                allergens: vec!["dairy".to_string(), "fish".to_string()],
+            }
+        );
+        assert_eq!(list.appearances("sqjhc"), 3);
+
+        let list = FoodList::parse("a b\n").unwrap();
+        assert!(list.foods[0].allergens.is_empty());
+
+        for bad in &["a (contains dairy", "a (contains )", "a (b) (contains c)"] {
+            assert_eq!(FoodList::parse(bad).unwrap_err().line, 1);
+        }
+    }
+}
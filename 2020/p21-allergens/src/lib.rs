@@ -0,0 +1,2 @@
+pub mod food;
+pub mod solve;
@@ -1,97 +1,36 @@
-use std::collections::HashMap;
-use std::collections::HashSet;
+use p21_allergens::food::FoodList;
+use p21_allergens::solve::{canonical_list, Analysis};
 
 fn main() {
-    let filename = std::env::args().nth(1).expect("No filename given");
-    let mut all_allergens = HashSet::new();
-    let mut all_ingredients = HashSet::new();
-    let food: Vec<_> = std::fs::read_to_string(filename)
-        .expect("Bad input file")
-        .replace(")", "")
-        .lines()
-        .map(|l| {
-            let mut parts = l.split(" (contains ");
-            let ingredients: HashSet<String> =
-                parts.next().unwrap().split(' ').map(String::from).collect();
-            let allergens: HashSet<String> = parts
-                .next()
-                .unwrap()
-                .split(", ")
-                .map(String::from)
-                .collect();
-            all_allergens.extend(allergens.clone());
-            all_ingredients.extend(ingredients.clone());
-            (ingredients, allergens)
-        })
-        .collect();
-    let mut maybe_with_allergen = HashMap::with_capacity(all_allergens.len());
-    for (ingredients, allergens) in &food {
-        for allergen in allergens {
-            let known_allergens = maybe_with_allergen
-                .entry(allergen)
-                .or_insert_with(|| ingredients.to_owned());
-            *known_allergens = known_allergens
-                .intersection(&ingredients)
-                .cloned()
-                .collect();
-        }
-    }
-
-    let ingredients_that_appear = maybe_with_allergen
-        .values()
-        .fold(HashSet::new(), |ingredients, acc| {
-            acc.union(&ingredients).cloned().collect()
-        });
-    dbg!(&ingredients_that_appear.len());
-    dbg!(&all_allergens.len());
-    let those_that_not: HashSet<_> = all_ingredients
-        .difference(&ingredients_that_appear)
-        .collect();
+    let mut args = std::env::args().skip(1);
+    let filename = args.next().expect("No filename given");
+    let explain = args.next().as_deref() == Some("explain");
 
-    let mut times = 0;
-    for (ingredients, _allergens) in &food {
-        for ingredient in ingredients {
-            if those_that_not.contains(&ingredient) {
-                times += 1;
-            }
+    let list = match FoodList::parse(&std::fs::read_to_string(filename).expect("Bad input file")) {
+        Ok(list) => list,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
         }
+    };
+    let analysis = Analysis::new(&list);
+    if explain {
+        let safe: Vec<_> = analysis.safe_ingredients().into_iter().collect();
+        println!("Safe: {}", safe.join(" "));
     }
-    dbg!(times);
+    println!("Part 1 {}", analysis.safe_appearances());
 
-    // now to solve them (Part 2)
-    let mut allergen_mapping: HashMap<String, String> = HashMap::new(); // from allergen to ingredient
-    while let Some(allergen) =
-        maybe_with_allergen
-            .iter()
-            .find_map(|(allergen, maybe_ingredients)| {
-                if maybe_ingredients.len() == 1 {
-                    Some(allergen.to_string())
-                } else {
-                    None
-                }
-            })
-    {
-        // remove the allergen and get the dangerous ingredient
-        let dangerous_ingredient = maybe_with_allergen
-            .remove(&allergen)
-            .unwrap()
-            .into_iter()
-            .next()
-            .unwrap();
-        // remove the ingredient from all the remaining maybe ingredients
-        for set in maybe_with_allergen.values_mut() {
-            set.remove(&dangerous_ingredient);
+    let assignments = match analysis.solve() {
+        Ok(assignments) => assignments,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    if explain {
+        for assignment in &assignments {
+            println!("{}", assignment);
         }
-        allergen_mapping.insert(allergen, dangerous_ingredient);
     }
-
-    let mut allergen_mapping: Vec<_> = allergen_mapping.iter().collect();
-    allergen_mapping.sort_unstable();
-    let ans = allergen_mapping
-        .iter()
-        .map(|(_allergen, ingredient)| ingredient.to_string())
-        .collect::<Vec<_>>()
-        .join(",");
-
-    println!("{}", ans);
+    println!("Part 2 {}", canonical_list(&assignments));
 }
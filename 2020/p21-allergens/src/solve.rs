@@ -0,0 +1,233 @@
+use crate::food::FoodList;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// no ingredient can hold the allergen: the foods on these lines share
+    /// none, or the ones they share hold other allergens
+    Contradiction { allergen: String, lines: Vec<usize> },
+    /// the allergens left could each be in more than one ingredient
+    Underdetermined(BTreeMap<String, BTreeSet<String>>),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::Contradiction { allergen, lines } => write!(
+                f,
+                "no ingredient can hold {}, listed on lines {:?}",
+                allergen, lines
+            ),
+            SolveError::Underdetermined(left) => {
+                write!(f, "can't tell apart")?;
+                for (allergen, ingredients) in left {
+                    let ingredients: Vec<_> = ingredients.iter().map(String::as_str).collect();
+                    write!(f, "; {} could be in {}", allergen, ingredients.join(" or "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Why an allergen is in an ingredient
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assignment {
+    pub allergen: String,
+    pub ingredient: String,
+    /// the lines listing the allergen, every one of them has the ingredient
+    pub lines: Vec<usize>,
+    /// the other ingredients those lines share, with the allergen each one
+    /// was found to hold first
+    pub ruled_out: Vec<(String, String)>,
+}
+
+impl fmt::Display for Assignment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lines: Vec<_> = self.lines.iter().map(|l| l.to_string()).collect();
+        write!(f, "{} is in {}: ", self.allergen, self.ingredient)?;
+        match lines.as_slice() {
+            [line] => write!(f, "line {} has it", line)?,
+            lines => write!(f, "lines {} all have it", lines.join(", "))?,
+        }
+        for (ingredient, allergen) in &self.ruled_out {
+            write!(f, ", {} has {}", ingredient, allergen)?;
+        }
+        Ok(())
+    }
+}
+
+/// Which ingredients can hold which allergens. Each allergen is in exactly
+/// one ingredient, so it can only be in the ingredients shared by every food
+/// listing it.
+#[derive(Debug, Clone)]
+pub struct Analysis<'a> {
+    list: &'a FoodList,
+    candidates: BTreeMap<String, BTreeSet<String>>,
+    /// 1 based lines listing each allergen
+    lines: BTreeMap<String, Vec<usize>>,
+}
+
+impl<'a> Analysis<'a> {
+    pub fn new(list: &'a FoodList) -> Analysis<'a> {
+        let mut candidates: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut lines: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (line, food) in list.foods.iter().enumerate() {
+            let ingredients: BTreeSet<String> = food.ingredients.iter().cloned().collect();
+            for allergen in &food.allergens {
+                lines.entry(allergen.clone()).or_default().push(line + 1);
+                candidates
+                    .entry(allergen.clone())
+                    .and_modify(|c| c.retain(|i| ingredients.contains(i)))
+                    .or_insert_with(|| ingredients.clone());
+            }
+        }
+        Analysis {
+            list,
+            candidates,
+            lines,
+        }
+    }
+
+    /// The ingredients each allergen may be in
+    pub fn candidates(&self) -> &BTreeMap<String, BTreeSet<String>> {
+        &self.candidates
+    }
+
+    /// Ingredients that can't hold any allergen
+    pub fn safe_ingredients(&self) -> BTreeSet<&str> {
+        self.list
+            .foods
+            .iter()
+            .flat_map(|f| f.ingredients.iter())
+            .filter(|i| self.candidates.values().all(|c| !c.contains(*i)))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// How many times safe ingredients show up in the list
+    pub fn safe_appearances(&self) -> usize {
+        self.safe_ingredients()
+            .iter()
+            .map(|i| self.list.appearances(i))
+            .sum()
+    }
+
+    /// Repeatedly settles an allergen left with a single ingredient and takes
+    /// that ingredient away from the others
+    pub fn solve(&self) -> Result<Vec<Assignment>, SolveError> {
+        let mut left = self.candidates.clone();
+        // ingredient to the allergen it was found to hold
+        let mut holds: BTreeMap<String, String> = BTreeMap::new();
+        let mut assignments = Vec::with_capacity(left.len());
+        while !left.is_empty() {
+            if let Some((allergen, _)) = left.iter().find(|(_, c)| c.is_empty()) {
+                return Err(SolveError::Contradiction {
+                    allergen: allergen.clone(),
+                    lines: self.lines[allergen].clone(),
+                });
+            }
+            let allergen = match left.iter().find(|(_, c)| c.len() == 1) {
+                Some((allergen, _)) => allergen.clone(),
+                None => return Err(SolveError::Underdetermined(left)),
+            };
+            let ingredient = left.remove(&allergen).unwrap().into_iter().next().unwrap();
+            for c in left.values_mut() {
+                c.remove(&ingredient);
+            }
+            let ruled_out = self.candidates[&allergen]
+                .iter()
+                .filter(|&i| *i != ingredient)
+                .map(|i| (i.clone(), holds[i].clone()))
+                .collect();
+            holds.insert(ingredient.clone(), allergen.clone());
+            assignments.push(Assignment {
+                lines: self.lines[&allergen].clone(),
+                allergen,
+                ingredient,
+                ruled_out,
+            });
+        }
+        Ok(assignments)
+    }
+}
+
+/// The dangerous ingredients sorted by the allergen they hold
+pub fn canonical_list(assignments: &[Assignment]) -> String {
+    let mut sorted: Vec<_> = assignments.iter().collect();
+    sorted.sort_unstable_by(|a, b| a.allergen.cmp(&b.allergen));
+    sorted
+        .iter()
+        .map(|a| a.ingredient.as_str())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(input: &str) -> FoodList {
+        FoodList::parse(input).unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        let list = list(&std::fs::read_to_string("data/test1.txt").unwrap());
+        let analysis = Analysis::new(&list);
+        let safe: Vec<_> = analysis.safe_ingredients().into_iter().collect();
+        assert_eq!(safe, vec!["kfcds", "nhms", "sbzzf", "trh"]);
+        assert_eq!(analysis.safe_appearances(), 5);
+
+        let assignments = analysis.solve().unwrap();
+        assert_eq!(canonical_list(&assignments), "mxmxvkd,sqjhc,fvjkl");
+        let explained: Vec<_> = assignments.iter().map(|a| a.to_string()).collect();
+        assert_eq!(
+            explained,
+            vec![
+                "dairy is in mxmxvkd: lines 1, 2 all have it",
+                "fish is in sqjhc: lines 1, 4 all have it, mxmxvkd has dairy",
+                "soy is in fvjkl: line 3 has it, sqjhc has fish",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inputs() {
+        let list = list(&std::fs::read_to_string("data/input.txt").unwrap());
+        let analysis = Analysis::new(&list);
+        assert_eq!(analysis.safe_appearances(), 1829);
+        assert_eq!(
+            canonical_list(&analysis.solve().unwrap()),
+            "mxkh,gkcqxs,bvh,sp,rgc,krjn,bpbdlmg,tdbcfb"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let foods = list("a b (contains x)\nc d (contains x)");
+        assert_eq!(
+            Analysis::new(&foods).solve(),
+            Err(SolveError::Contradiction {
+                allergen: "x".to_string(),
+                lines: vec![1, 2]
+            })
+        );
+        // both allergens can only be in a
+        let foods = list("a (contains x)\na b (contains y)\na (contains y)");
+        assert_eq!(
+            Analysis::new(&foods).solve(),
+            Err(SolveError::Contradiction {
+                allergen: "y".to_string(),
+                lines: vec![2, 3]
+            })
+        );
+        let foods = list("a b (contains x, y)\nc");
+        let error = Analysis::new(&foods).solve().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "can't tell apart; x could be in a or b; y could be in a or b"
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+pub type Card = u16;
+pub type Score = usize;
+pub type Deck = VecDeque<Card>;
+
+/// When a round is decided by a sub-game instead of by the highest card
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recursion {
+    Never,
+    /// when every player holds enough cards to build their sub-game deck
+    WhenEnoughCards,
+}
+
+/// How many cards a player takes into a sub-game after drawing `card`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubDeck {
+    /// as many as the value of the drawn card
+    CardValue,
+    /// every card left in the deck
+    Remaining,
+}
+
+impl SubDeck {
+    fn size(self, card: Card, remaining: usize) -> usize {
+        match self {
+            SubDeck::CardValue => card as usize,
+            SubDeck::Remaining => remaining,
+        }
+    }
+}
+
+/// What ends a game that would otherwise go on forever. The first player
+/// still in the game wins it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopGuard {
+    /// nothing, games that loop never end
+    Off,
+    /// the decks being exactly as in a previous round of the same game
+    SameDecks,
+    /// the scores of the decks being as in a previous round. Cheaper, but
+    /// different decks can have the same scores.
+    SameScores,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    pub recursion: Recursion,
+    pub sub_deck: SubDeck,
+    pub loop_guard: LoopGuard,
+    /// remember who won each sub-game by the decks it started with
+    pub cache: bool,
+}
+
+impl Rules {
+    /// The highest card always wins
+    pub fn combat() -> Rules {
+        Rules {
+            recursion: Recursion::Never,
+            sub_deck: SubDeck::CardValue,
+            loop_guard: LoopGuard::SameDecks,
+            cache: false,
+        }
+    }
+
+    pub fn recursive_combat() -> Rules {
+        Rules {
+            recursion: Recursion::WhenEnoughCards,
+            sub_deck: SubDeck::CardValue,
+            loop_guard: LoopGuard::SameDecks,
+            cache: true,
+        }
+    }
+
+    pub fn with_recursion(mut self, recursion: Recursion) -> Rules {
+        self.recursion = recursion;
+        self
+    }
+
+    pub fn with_sub_deck(mut self, sub_deck: SubDeck) -> Rules {
+        self.sub_deck = sub_deck;
+        self
+    }
+
+    pub fn with_loop_guard(mut self, loop_guard: LoopGuard) -> Rules {
+        self.loop_guard = loop_guard;
+        self
+    }
+
+    pub fn with_cache(mut self, cache: bool) -> Rules {
+        self.cache = cache;
+        self
+    }
+}
+
+/// How much work a game took, sub-games included
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// sub-games actually played, not counting the ones found in the cache
+    pub sub_games: usize,
+    pub cache_hits: usize,
+    /// deepest nesting of sub-games, 0 if there were none
+    pub max_depth: usize,
+    /// rounds played over all games
+    pub rounds: usize,
+    /// games ended by the loop guard
+    pub loops: usize,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} sub-games ({} cached), max depth {}, {} rounds, {} loops",
+            self.sub_games, self.cache_hits, self.max_depth, self.rounds, self.loops
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// index of the winning player
+    pub winner: usize,
+    pub decks: Vec<Deck>,
+}
+
+impl Outcome {
+    pub fn score(&self) -> Score {
+        score(&self.decks[self.winner])
+    }
+}
+
+pub fn score(deck: &Deck) -> Score {
+    deck.iter()
+        .rev()
+        .enumerate()
+        .map(|(idx, card)| *card as Score * (idx + 1))
+        .sum::<Score>()
+}
+
+/// Plays games of Combat with any number of players. Each round every player
+/// with cards draws one, and the winner of the round puts its own card at the
+/// bottom of its deck followed by the others from highest to lowest, which
+/// for two players is the usual order. Players run out of cards until only
+/// one is left.
+#[derive(Debug, Clone)]
+pub struct Game {
+    rules: Rules,
+    cache: HashMap<Vec<Deck>, usize>,
+    stats: Stats,
+}
+
+impl Game {
+    pub fn new(rules: Rules) -> Game {
+        Game {
+            rules,
+            cache: HashMap::new(),
+            stats: Stats::default(),
+        }
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    pub fn play(&mut self, decks: Vec<Deck>) -> Outcome {
+        self.game(decks, 0)
+    }
+
+    fn sub_game(&mut self, decks: Vec<Deck>, depth: usize) -> usize {
+        if self.rules.cache {
+            if let Some(&winner) = self.cache.get(&decks) {
+                self.stats.cache_hits += 1;
+                return winner;
+            }
+        }
+        self.stats.sub_games += 1;
+        self.stats.max_depth = self.stats.max_depth.max(depth);
+        let key = if self.rules.cache {
+            Some(decks.clone())
+        } else {
+            None
+        };
+        let winner = self.game(decks, depth).winner;
+        if let Some(key) = key {
+            self.cache.insert(key, winner);
+        }
+        winner
+    }
+
+    fn game(&mut self, mut decks: Vec<Deck>, depth: usize) -> Outcome {
+        let mut seen_decks = HashSet::new();
+        let mut seen_scores = HashSet::new();
+        loop {
+            let active: Vec<usize> = (0..decks.len()).filter(|&p| !decks[p].is_empty()).collect();
+            if active.len() < 2 {
+                return Outcome {
+                    winner: active.first().cloned().unwrap_or(0),
+                    decks,
+                };
+            }
+            let repeated = match self.rules.loop_guard {
+                LoopGuard::Off => false,
+                LoopGuard::SameDecks => !seen_decks.insert(decks.clone()),
+                LoopGuard::SameScores => {
+                    !seen_scores.insert(decks.iter().map(score).collect::<Vec<_>>())
+                }
+            };
+            if repeated {
+                self.stats.loops += 1;
+                return Outcome {
+                    winner: active[0],
+                    decks,
+                };
+            }
+            self.stats.rounds += 1;
+
+            let mut drawn: Vec<(usize, Card)> = active
+                .iter()
+                .map(|&p| (p, decks[p].pop_front().unwrap()))
+                .collect();
+            let sizes: Vec<usize> = drawn
+                .iter()
+                .map(|&(p, card)| self.rules.sub_deck.size(card, decks[p].len()))
+                .collect();
+            let recurse = self.rules.recursion == Recursion::WhenEnoughCards
+                && drawn
+                    .iter()
+                    .zip(&sizes)
+                    .all(|(&(p, _), &size)| decks[p].len() >= size);
+            let winner = if recurse {
+                let sub_decks = drawn
+                    .iter()
+                    .zip(&sizes)
+                    .map(|(&(p, _), &size)| decks[p].iter().take(size).cloned().collect())
+                    .collect();
+                drawn[self.sub_game(sub_decks, depth + 1)].0
+            } else {
+                // the first of the highest cards if there are repeated ones
+                drawn
+                    .iter()
+                    .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+                    .unwrap()
+                    .0
+            };
+
+            drawn.sort_by_key(|&(p, card)| (p != winner, std::cmp::Reverse(card)));
+            decks[winner].extend(drawn.into_iter().map(|(_, card)| card));
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// decks start with a `Player N:` line
+    BadHeader(String),
+    BadCard(String),
+    NoPlayers,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::BadHeader(line) => write!(f, "expected \"Player N:\", found {:?}", line),
+            ParseError::BadCard(line) => write!(f, "bad card {:?}", line),
+            ParseError::NoPlayers => write!(f, "no players"),
+        }
+    }
+}
+
+/// Decks separated by blank lines, each after its `Player N:` header
+pub fn parse_decks(input: &str) -> Result<Vec<Deck>, ParseError> {
+    let decks = input
+        .split("\n\n")
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| {
+            let mut lines = part.lines().map(str::trim).filter(|l| !l.is_empty());
+            let header = lines.next().unwrap_or_default();
+            if !(header.starts_with("Player") && header.ends_with(':')) {
+                return Err(ParseError::BadHeader(header.to_string()));
+            }
+            lines
+                .map(|l| l.parse().map_err(|_| ParseError::BadCard(l.to_string())))
+                .collect()
+        })
+        .collect::<Result<Vec<Deck>, _>>()?;
+    if decks.is_empty() {
+        return Err(ParseError::NoPlayers);
+    }
+    Ok(decks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Vec<Deck> {
+        parse_decks(&std::fs::read_to_string("data/test1.txt").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_combat() {
+        let mut game = Game::new(Rules::combat());
+        let outcome = game.play(example());
+        assert_eq!((outcome.winner, outcome.score()), (1, 306));
+        assert_eq!(game.stats().rounds, 29);
+        assert_eq!(game.stats().sub_games, 0);
+    }
+
+    #[test]
+    fn test_recursive_combat() {
+        for &cache in &[true, false] {
+            let mut game = Game::new(Rules::recursive_combat().with_cache(cache));
+            let outcome = game.play(example());
+            assert_eq!((outcome.winner, outcome.score()), (1, 291));
+            assert_eq!(game.stats().max_depth, 2);
+        }
+    }
+
+    #[test]
+    fn test_loop_guard() {
+        let decks = parse_decks("Player 1:\n43\n19\n\nPlayer 2:\n2\n29\n14").unwrap();
+        let mut game = Game::new(Rules::recursive_combat());
+        assert_eq!(game.play(decks).winner, 0);
+        assert_eq!(game.stats().loops, 1);
+    }
+
+    #[test]
+    fn test_three_players() {
+        let decks = parse_decks("Player 1:\n1\n9\n\nPlayer 2:\n5\n2\n\nPlayer 3:\n3\n4").unwrap();
+        let outcome = Game::new(Rules::combat()).play(decks);
+        // 5 takes the first round and 9 every round after it
+        assert_eq!(outcome.winner, 0);
+        assert_eq!(outcome.decks[0].len(), 6);
+        assert!(outcome.decks[1].is_empty() && outcome.decks[2].is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_decks("Dealer:\n1"),
+            Err(ParseError::BadHeader("Dealer:".to_string()))
+        );
+        assert_eq!(
+            parse_decks("Player 1:\nx"),
+            Err(ParseError::BadCard("x".to_string()))
+        );
+        assert_eq!(parse_decks("\n"), Err(ParseError::NoPlayers));
+    }
+}
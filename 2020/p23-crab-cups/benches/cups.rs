@@ -0,0 +1,16 @@
+//! 10 million moves on a million cups, run with `cargo bench`
+use p23_crab_cups::cups::CupRing;
+use std::time::Instant;
+
+fn main() {
+    let start = Instant::now();
+    let mut ring = CupRing::parse("389125467", 1_000_000, 3).unwrap();
+    let built = start.elapsed();
+    ring.run(10_000_000);
+    let elapsed = start.elapsed();
+    assert_eq!(ring.product_after(1, 2), Some(149245887792));
+    println!(
+        "10M moves on 1M cups: {:?} ({:?} building the ring)",
+        elapsed, built
+    );
+}
@@ -0,0 +1,253 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+pub type Cup = u32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RingError {
+    NoCups,
+    BadLabel(char),
+    /// labels go from 1 to the number of cups
+    OutOfRange(Cup),
+    Repeated(Cup),
+    /// a move needs the current cup, the picked up ones and a destination
+    TooFewCups {
+        cups: usize,
+        pick_up: usize,
+    },
+}
+
+impl fmt::Display for RingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RingError::NoCups => write!(f, "no cups"),
+            RingError::BadLabel(c) => write!(f, "bad cup label {:?}", c),
+            RingError::OutOfRange(cup) => write!(f, "cup {} is out of range", cup),
+            RingError::Repeated(cup) => write!(f, "cup {} is repeated", cup),
+            RingError::TooFewCups { cups, pick_up } => {
+                write!(f, "{} cups are too few to pick up {}", cups, pick_up)
+            }
+        }
+    }
+}
+
+/// Cups labelled 1 to n in a circle. Each cup knows the label of the one
+/// clockwise from it, so a move only relinks a few cups.
+#[derive(Debug, Clone)]
+pub struct CupRing {
+    /// `next[cup]` is the cup after `cup`, `next[0]` is unused
+    next: Vec<Cup>,
+    current: Cup,
+    pick_up: usize,
+}
+
+impl CupRing {
+    /// `start` are the first cups clockwise, the first one being the current
+    /// cup. Up to `total` cups, the labels missing from `start` follow in
+    /// increasing order.
+    pub fn new(start: &[Cup], total: usize, pick_up: usize) -> Result<CupRing, RingError> {
+        let total = total.max(start.len());
+        if start.is_empty() {
+            return Err(RingError::NoCups);
+        }
+        if total < pick_up + 2 {
+            return Err(RingError::TooFewCups {
+                cups: total,
+                pick_up,
+            });
+        }
+        let max = Cup::try_from(total).map_err(|_| RingError::OutOfRange(Cup::MAX))?;
+
+        let mut seen = vec![false; total + 1];
+        for &cup in start {
+            if cup == 0 || cup > max {
+                return Err(RingError::OutOfRange(cup));
+            }
+            if seen[cup as usize] {
+                return Err(RingError::Repeated(cup));
+            }
+            seen[cup as usize] = true;
+        }
+        let rest = (1..=max).filter(|&cup| !seen[cup as usize]);
+        let order = start.iter().cloned().chain(rest);
+
+        let mut next = vec![0; total + 1];
+        let mut prev = start[0];
+        for cup in order.skip(1) {
+            next[prev as usize] = cup;
+            prev = cup;
+        }
+        next[prev as usize] = start[0];
+        Ok(CupRing {
+            next,
+            current: start[0],
+            pick_up,
+        })
+    }
+
+    /// Cups given as digits, like `389125467`
+    pub fn parse(digits: &str, total: usize, pick_up: usize) -> Result<CupRing, RingError> {
+        let start = digits
+            .trim()
+            .chars()
+            .map(|c| c.to_digit(10).ok_or(RingError::BadLabel(c)))
+            .collect::<Result<Vec<Cup>, _>>()?;
+        CupRing::new(&start, total, pick_up)
+    }
+
+    pub fn len(&self) -> usize {
+        self.next.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn current(&self) -> Cup {
+        self.current
+    }
+
+    pub fn next(&self, cup: Cup) -> Cup {
+        self.next[cup as usize]
+    }
+
+    /// Picks up the cups after the current one, puts them after the
+    /// destination cup and moves on to the next cup
+    pub fn step(&mut self) {
+        let max = self.len() as Cup;
+        let first = self.next(self.current);
+        let mut last = self.current;
+        for _ in 0..self.pick_up {
+            last = self.next(last);
+        }
+        let picked_up = |cup: Cup| {
+            let mut c = first;
+            for _ in 0..self.pick_up {
+                if c == cup {
+                    return true;
+                }
+                c = self.next(c);
+            }
+            false
+        };
+
+        let mut destination = self.current;
+        loop {
+            destination = if destination == 1 {
+                max
+            } else {
+                destination - 1
+            };
+            if !picked_up(destination) {
+                break;
+            }
+        }
+
+        if self.pick_up > 0 {
+            self.next[self.current as usize] = self.next(last);
+            self.next[last as usize] = self.next(destination);
+            self.next[destination as usize] = first;
+        }
+        self.current = self.next(self.current);
+    }
+
+    pub fn run(&mut self, moves: usize) {
+        for _ in 0..moves {
+            self.step();
+        }
+    }
+
+    /// The other cups clockwise from `cup`
+    pub fn after(&self, cup: Cup) -> impl Iterator<Item = Cup> + '_ {
+        let mut c = cup;
+        (1..self.len()).map(move |_| {
+            c = self.next(c);
+            c
+        })
+    }
+
+    /// The labels of the cups after cup 1, one after the other
+    pub fn labels_after_1(&self) -> String {
+        self.after(1).map(|cup| cup.to_string()).collect()
+    }
+
+    /// Product of the labels of the `k` cups after `cup`, None if it overflows
+    pub fn product_after(&self, cup: Cup, k: usize) -> Option<u64> {
+        self.after(cup)
+            .take(k)
+            .try_fold(1u64, |p, c| p.checked_mul(u64::from(c)))
+    }
+}
+
+/// Clockwise from the current cup, which is in parentheses
+impl fmt::Display for CupRing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({})", self.current)?;
+        for cup in self.after(self.current) {
+            write!(f, " {}", cup)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let mut ring = CupRing::parse("389125467", 9, 3).unwrap();
+        ring.step();
+        assert_eq!(ring.to_string(), "(2) 8 9 1 5 4 6 7 3");
+        ring.run(9);
+        assert_eq!(ring.labels_after_1(), "92658374");
+        ring.run(90);
+        assert_eq!(ring.labels_after_1(), "67384529");
+    }
+
+    #[test]
+    fn test_many_cups() {
+        let mut ring = CupRing::parse("389125467", 20, 3).unwrap();
+        assert_eq!(ring.len(), 20);
+        assert_eq!(ring.next(7), 10);
+        assert_eq!(ring.next(20), 3);
+        ring.run(1000);
+        assert_eq!(ring.after(1).count(), 19);
+        assert!(ring.product_after(1, 2).is_some());
+    }
+
+    #[test]
+    fn test_pick_up_sizes() {
+        // nothing picked up, the current cup just moves on
+        let mut ring = CupRing::parse("312", 3, 0).unwrap();
+        ring.run(2);
+        assert_eq!(ring.to_string(), "(2) 3 1");
+        let mut ring = CupRing::parse("54321", 5, 1).unwrap();
+        ring.step();
+        assert_eq!(ring.to_string(), "(3) 4 2 1 5");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(CupRing::parse("", 9, 3).unwrap_err(), RingError::NoCups);
+        assert_eq!(
+            CupRing::parse("3a", 9, 3).unwrap_err(),
+            RingError::BadLabel('a')
+        );
+        assert_eq!(
+            CupRing::parse("39", 5, 3).unwrap_err(),
+            RingError::OutOfRange(9)
+        );
+        assert_eq!(
+            CupRing::parse("33", 5, 3).unwrap_err(),
+            RingError::Repeated(3)
+        );
+        assert_eq!(
+            CupRing::parse("1234", 4, 3).unwrap_err(),
+            RingError::TooFewCups {
+                cups: 4,
+                pick_up: 3
+            }
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod life;
+pub mod op;
+pub mod parse;
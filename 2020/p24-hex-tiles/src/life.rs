@@ -0,0 +1,237 @@
+use crate::op::{EisensteinInt, Op, ORIGIN};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// Which tiles are black the next day, by their number of black neighbours
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    /// bit n set if a white tile with n black neighbours turns black
+    birth: u8,
+    /// bit n set if a black tile with n black neighbours stays black
+    survival: u8,
+}
+
+impl Rule {
+    /// None if a count is past the six neighbours a tile has, or if tiles
+    /// with no black neighbours turn black, as the whole floor would
+    pub fn new(birth: &[usize], survival: &[usize]) -> Option<Rule> {
+        if birth.contains(&0) {
+            return None;
+        }
+        let mask = |counts: &[usize]| {
+            counts
+                .iter()
+                .try_fold(0u8, |m, &n| if n <= 6 { Some(m | 1 << n) } else { None })
+        };
+        Some(Rule {
+            birth: mask(birth)?,
+            survival: mask(survival)?,
+        })
+    }
+
+    /// The lobby floor: a black tile with zero or more than 2 black
+    /// neighbours is flipped to white, a white tile with exactly 2 black
+    /// neighbours is flipped to black
+    pub fn lobby() -> Rule {
+        Rule::new(&[2], &[1, 2]).unwrap()
+    }
+
+    pub fn next(&self, black: bool, neighbours: usize) -> bool {
+        let mask = if black { self.survival } else { self.birth };
+        neighbours <= 6 && mask & (1 << neighbours) != 0
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BadRule(String);
+
+impl fmt::Display for BadRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "bad rule {:?}, expected something like B2/S12", self.0)
+    }
+}
+
+/// Parses rules in the usual B/S notation, for example `B2/S12`
+impl FromStr for Rule {
+    type Err = BadRule;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = || BadRule(s.to_string());
+        let mut parts = s.trim().split('/');
+        let mut counts = |prefix: char| -> Result<Vec<usize>, BadRule> {
+            let part = parts.next().ok_or_else(bad)?;
+            let digits = part.strip_prefix(prefix).ok_or_else(bad)?;
+            digits
+                .chars()
+                .map(|c| c.to_digit(10).map(|d| d as usize).ok_or_else(bad))
+                .collect()
+        };
+        let birth = counts('B')?;
+        let survival = counts('S')?;
+        if parts.next().is_some() {
+            return Err(bad());
+        }
+        Rule::new(&birth, &survival).ok_or_else(bad)
+    }
+}
+
+/// An infinite hexagonal floor with some tiles flipped to black
+#[derive(Debug, Clone)]
+pub struct HexLife {
+    black: HashSet<EisensteinInt>,
+    rule: Rule,
+}
+
+impl HexLife {
+    pub fn new(rule: Rule) -> HexLife {
+        HexLife {
+            black: HashSet::new(),
+            rule,
+        }
+    }
+
+    /// Flips the tile at the end of each path from the reference tile
+    pub fn from_paths(paths: &[Vec<Op>], rule: Rule) -> HexLife {
+        let mut life = HexLife::new(rule);
+        for path in paths {
+            life.flip(ORIGIN.walk(path));
+        }
+        life
+    }
+
+    pub fn flip(&mut self, tile: EisensteinInt) {
+        if !self.black.remove(&tile) {
+            self.black.insert(tile);
+        }
+    }
+
+    pub fn is_black(&self, tile: EisensteinInt) -> bool {
+        self.black.contains(&tile)
+    }
+
+    pub fn black(&self) -> impl Iterator<Item = &EisensteinInt> {
+        self.black.iter()
+    }
+
+    pub fn count(&self) -> usize {
+        self.black.len()
+    }
+
+    /// Only the tiles next to a black one have black neighbours, so counting
+    /// from the black tiles finds every tile that may be black tomorrow
+    pub fn step(&mut self) {
+        let mut counts: HashMap<EisensteinInt, usize> = HashMap::new();
+        for tile in &self.black {
+            for neighbour in tile.neighbours() {
+                *counts.entry(neighbour).or_default() += 1;
+            }
+        }
+        let rule = self.rule;
+        let black = &self.black;
+        let mut next: HashSet<EisensteinInt> = counts
+            .into_iter()
+            .filter(|&(tile, n)| rule.next(black.contains(&tile), n))
+            .map(|(tile, _)| tile)
+            .collect();
+        // isolated black tiles never show up in the counts
+        if rule.next(true, 0) {
+            next.extend(
+                black
+                    .iter()
+                    .filter(|t| t.neighbours().all(|n| !black.contains(&n))),
+            );
+        }
+        self.black = next;
+    }
+
+    pub fn run(&mut self, days: usize) {
+        for _ in 0..days {
+            self.step();
+        }
+    }
+}
+
+/// Rows from north to south, each tile being `#` if black or `.` if white.
+/// Every row is shifted half a tile from the one above it, like the floor.
+impl fmt::Display for HexLife {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.black.is_empty() {
+            return writeln!(f, ".");
+        }
+        // doubled coordinates: east is two columns, south east is one
+        let column = |t: &EisensteinInt| {
+            let (q, r) = t.to_axial();
+            2 * q + r
+        };
+        let rows = self.black.iter().map(|t| t.to_axial().1);
+        let (top, bottom) = (rows.clone().min().unwrap(), rows.max().unwrap());
+        let columns = self.black.iter().map(column);
+        let (left, right) = (columns.clone().min().unwrap(), columns.max().unwrap());
+        for r in top..=bottom {
+            let line: String = (left..=right)
+                .map(|x| {
+                    if (x - r).rem_euclid(2) != 0 {
+                        ' '
+                    } else if self.is_black(EisensteinInt::from_axial(((x - r) / 2, r))) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    #[test]
+    fn test_example() {
+        let paths = parse(&std::fs::read_to_string("data/test1.txt").unwrap()).unwrap();
+        let mut life = HexLife::from_paths(&paths, Rule::lobby());
+        assert_eq!(life.count(), 10);
+        life.step();
+        assert_eq!(life.count(), 15);
+        life.run(9);
+        assert_eq!(life.count(), 37);
+        life.run(90);
+        assert_eq!(life.count(), 2208);
+    }
+
+    #[test]
+    fn test_rules() {
+        assert_eq!("B2/S12".parse(), Ok(Rule::lobby()));
+        assert!("B7/S1".parse::<Rule>().is_err());
+        assert!("S1/B2".parse::<Rule>().is_err());
+        assert!("B0/S1".parse::<Rule>().is_err());
+
+        // a lone tile survives when 0 neighbours is enough
+        let mut life = HexLife::new("B/S0".parse().unwrap());
+        life.flip(ORIGIN);
+        life.run(3);
+        assert_eq!(life.black().collect::<Vec<_>>(), vec![&ORIGIN]);
+
+        // every neighbour of a lone tile is born
+        let mut life = HexLife::new("B1/S".parse().unwrap());
+        life.flip(ORIGIN);
+        life.step();
+        assert_eq!(life.count(), 6);
+        assert!(ORIGIN.ring(1).iter().all(|&t| life.is_black(t)));
+    }
+
+    #[test]
+    fn test_render() {
+        let mut life = HexLife::new(Rule::lobby());
+        life.flip(ORIGIN);
+        life.flip(ORIGIN.walk(&[Op::E, Op::E]));
+        life.flip(ORIGIN.walk(&[Op::SE]));
+        life.flip(ORIGIN.walk(&[Op::NW]));
+        assert_eq!(life.to_string(), "# . .\n # . #\n. # .\n");
+    }
+}
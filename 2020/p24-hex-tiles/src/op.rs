@@ -1,6 +1,5 @@
-#[allow(unused)]
-/// Eisenstein integers.
-#[derive(Debug)]
+/// A step to one of the six neighbouring tiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Op {
     NE,
     E,
@@ -12,6 +11,9 @@ pub enum Op {
 
 pub const OPS: [Op; 6] = [Op::NE, Op::E, Op::SE, Op::NW, Op::W, Op::SW];
 
+/// The steps counterclockwise starting east, the order to walk a ring in
+const AROUND: [Op; 6] = [Op::E, Op::NE, Op::NW, Op::W, Op::SW, Op::SE];
+
 impl Op {
     const fn shift(&self) -> (isize, isize) {
         match self {
@@ -23,18 +25,137 @@ impl Op {
             Op::SW => (-1, -1),
         }
     }
+
+    pub fn opposite(self) -> Op {
+        match self {
+            Op::NE => Op::SW,
+            Op::E => Op::W,
+            Op::SE => Op::NW,
+            Op::NW => Op::SE,
+            Op::W => Op::E,
+            Op::SW => Op::NE,
+        }
+    }
 }
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone)]
+/// Eisenstein integers `i + omega * ω`, where `ω` is the north west step.
+/// They address the tiles of a hexagonal floor with east and west to the
+/// sides, the north east step being `1 + ω`.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
 pub struct EisensteinInt {
     i: isize,
     omega: isize,
 }
 
+pub const ORIGIN: EisensteinInt = EisensteinInt { i: 0, omega: 0 };
+
 impl EisensteinInt {
     pub fn new(i: isize, omega: isize) -> Self {
         EisensteinInt { i, omega }
     }
+
+    /// Follows the steps from here
+    pub fn walk<'a>(self, ops: impl IntoIterator<Item = &'a Op>) -> EisensteinInt {
+        ops.into_iter().fold(self, |acc, op| &acc + op)
+    }
+
+    /// Axial coordinates `(q, r)` with `q` growing east and `r` south east
+    pub fn to_axial(self) -> (isize, isize) {
+        (self.i, -self.omega)
+    }
+
+    pub fn from_axial((q, r): (isize, isize)) -> EisensteinInt {
+        EisensteinInt::new(q, -r)
+    }
+
+    /// Cube coordinates `(x, y, z)`, which always add up to 0
+    pub fn to_cube(self) -> (isize, isize, isize) {
+        let (q, r) = self.to_axial();
+        (q, -q - r, r)
+    }
+
+    /// None if the coordinates don't add up to 0
+    pub fn from_cube((x, y, z): (isize, isize, isize)) -> Option<EisensteinInt> {
+        if x + y + z == 0 {
+            Some(EisensteinInt::from_axial((x, z)))
+        } else {
+            None
+        }
+    }
+
+    /// Number of steps between the two tiles
+    pub fn distance(self, other: EisensteinInt) -> usize {
+        let (x, y, z) = (self - other).to_cube();
+        (x.abs().max(y.abs()).max(z.abs())) as usize
+    }
+
+    pub fn neighbours(self) -> impl Iterator<Item = EisensteinInt> {
+        OPS.iter().map(move |op| &self + op)
+    }
+
+    /// The tiles exactly `radius` steps away, counterclockwise starting south
+    /// west
+    pub fn ring(self, radius: usize) -> Vec<EisensteinInt> {
+        if radius == 0 {
+            return vec![self];
+        }
+        let mut tile = self + EisensteinInt::step(Op::SW) * radius as isize;
+        let mut ring = Vec::with_capacity(6 * radius);
+        for op in &AROUND {
+            for _ in 0..radius {
+                ring.push(tile);
+                tile = &tile + op;
+            }
+        }
+        ring
+    }
+
+    /// The tiles up to `radius` steps away, ring after ring from the centre
+    pub fn spiral(self, radius: usize) -> Vec<EisensteinInt> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+
+    /// The tiles a straight line between the centres of both tiles crosses,
+    /// both ends included
+    pub fn line_to(self, other: EisensteinInt) -> Vec<EisensteinInt> {
+        let n = self.distance(other);
+        let (ax, ay, az) = self.to_cube();
+        let (bx, by, bz) = other.to_cube();
+        // nudged off the borders between tiles so the rounding is consistent
+        let lerp =
+            |a: isize, b: isize, t: f64, nudge: f64| a as f64 + 1e-6 * nudge + (b - a) as f64 * t;
+        (0..=n)
+            .map(|k| {
+                let t = if n == 0 { 0.0 } else { k as f64 / n as f64 };
+                cube_round(
+                    lerp(ax, bx, t, 1.0),
+                    lerp(ay, by, t, 2.0),
+                    lerp(az, bz, t, -3.0),
+                )
+            })
+            .collect()
+    }
+
+    fn step(op: Op) -> EisensteinInt {
+        let (i, omega) = op.shift();
+        EisensteinInt { i, omega }
+    }
+}
+
+/// The tile containing a point given in fractional cube coordinates
+fn cube_round(x: f64, y: f64, z: f64) -> EisensteinInt {
+    let (mut rx, mut ry, mut rz) = (x.round(), y.round(), z.round());
+    let (dx, dy, dz) = ((rx - x).abs(), (ry - y).abs(), (rz - z).abs());
+    // the coordinate that moved the most is fixed by the other two
+    if dx > dy && dx > dz {
+        rx = -ry - rz;
+    } else if dy > dz {
+        ry = -rx - rz;
+    } else {
+        rz = -rx - ry;
+    }
+    EisensteinInt::from_cube((rx as isize, ry as isize, rz as isize))
+        .expect("rounded coordinates add up to 0")
 }
 
 impl std::ops::Add<&Op> for &EisensteinInt {
@@ -56,3 +177,83 @@ impl std::ops::AddAssign<Op> for EisensteinInt {
         self.omega += shift_omega;
     }
 }
+
+impl std::ops::Add for EisensteinInt {
+    type Output = EisensteinInt;
+
+    fn add(self, other: EisensteinInt) -> EisensteinInt {
+        EisensteinInt::new(self.i + other.i, self.omega + other.omega)
+    }
+}
+
+impl std::ops::Sub for EisensteinInt {
+    type Output = EisensteinInt;
+
+    fn sub(self, other: EisensteinInt) -> EisensteinInt {
+        EisensteinInt::new(self.i - other.i, self.omega - other.omega)
+    }
+}
+
+impl std::ops::Mul<isize> for EisensteinInt {
+    type Output = EisensteinInt;
+
+    fn mul(self, k: isize) -> EisensteinInt {
+        EisensteinInt::new(self.i * k, self.omega * k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coordinates() {
+        let tile = ORIGIN.walk(&[Op::NE, Op::NE, Op::E, Op::SE]);
+        assert_eq!(tile.to_axial(), (3, -1));
+        assert_eq!(tile.to_cube(), (3, -2, -1));
+        assert_eq!(EisensteinInt::from_cube(tile.to_cube()), Some(tile));
+        assert_eq!(EisensteinInt::from_cube((1, 1, 1)), None);
+        assert_eq!(ORIGIN.distance(tile), 3);
+        // nwwswee flips the reference tile itself
+        let ops = [Op::NW, Op::W, Op::SW, Op::E, Op::E];
+        assert_eq!(ORIGIN.walk(&ops), ORIGIN);
+        for op in &OPS {
+            assert_eq!(ORIGIN.walk(&[*op, op.opposite()]), ORIGIN);
+        }
+    }
+
+    #[test]
+    fn test_rings() {
+        let centre = EisensteinInt::new(2, -1);
+        for radius in 0..5 {
+            let ring = centre.ring(radius);
+            assert_eq!(ring.len(), if radius == 0 { 1 } else { 6 * radius });
+            assert!(ring.iter().all(|t| t.distance(centre) == radius));
+            // each tile of the ring touches the next one
+            if radius > 0 {
+                for (a, b) in ring.iter().zip(ring.iter().cycle().skip(1)) {
+                    assert_eq!(a.distance(*b), 1);
+                }
+            }
+        }
+        let spiral = centre.spiral(3);
+        assert_eq!(spiral.len(), 37);
+        let mut unique = spiral.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 37);
+    }
+
+    #[test]
+    fn test_lines() {
+        let a = EisensteinInt::new(-2, 1);
+        let b = EisensteinInt::new(4, -3);
+        let line = a.line_to(b);
+        assert_eq!(line.len(), a.distance(b) + 1);
+        assert_eq!((line[0], line[line.len() - 1]), (a, b));
+        assert!(line.windows(2).all(|w| w[0].distance(w[1]) == 1));
+        assert_eq!(a.line_to(a), vec![a]);
+        let east: Vec<_> = (0..4).map(|k| EisensteinInt::new(k, 0)).collect();
+        assert_eq!(ORIGIN.line_to(EisensteinInt::new(3, 0)), east);
+    }
+}
@@ -0,0 +1,86 @@
+use crate::op::Op;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1 based
+    pub line: usize,
+    pub column: usize,
+    /// None if the line ends after `n` or `s`
+    pub found: Option<char>,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.found {
+            Some(c) => write!(f, "unexpected {:?} at {}:{}", c, self.line, self.column),
+            None => write!(f, "unfinished step at {}:{}", self.line, self.column),
+        }
+    }
+}
+
+/// One line of steps per tile, like `nwwswee`
+pub fn parse(input: &str) -> Result<Vec<Vec<Op>>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(line, l)| {
+            let mut was_north = None;
+            let mut ops = Vec::with_capacity(l.len() / 2);
+            for (column, c) in l.chars().enumerate() {
+                let push = match (was_north, c) {
+                    (None, 'e') => Ok(Op::E),
+                    (None, 'w') => Ok(Op::W),
+                    (Some(true), 'e') => Ok(Op::NE),
+                    (Some(true), 'w') => Ok(Op::NW),
+                    (Some(false), 'e') => Ok(Op::SE),
+                    (Some(false), 'w') => Ok(Op::SW),
+                    (None, 's') => Err(false),
+                    (None, 'n') => Err(true),
+                    _ => {
+                        return Err(ParseError {
+                            line: line + 1,
+                            column: column + 1,
+                            found: Some(c),
+                        })
+                    }
+                };
+                match push {
+                    Ok(op) => {
+                        ops.push(op);
+                        was_north = None
+                    }
+                    Err(new_was_north) => was_north = Some(new_was_north),
+                }
+            }
+            if was_north.is_some() {
+                return Err(ParseError {
+                    line: line + 1,
+                    column: l.chars().count() + 1,
+                    found: None,
+                });
+            }
+            Ok(ops)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("esenee\nnwwswee"),
+            Ok(vec![
+                vec![Op::E, Op::SE, Op::NE, Op::E],
+                vec![Op::NW, Op::W, Op::SW, Op::E, Op::E]
+            ])
+        );
+        let err = parse("e\nwnsw").unwrap_err();
+        assert_eq!((err.line, err.column, err.found), (2, 3, Some('s')));
+        let err = parse("es").unwrap_err();
+        assert_eq!((err.line, err.column, err.found), (1, 3, None));
+    }
+}
@@ -1,27 +1,14 @@
-const P: usize = 20201227;
+use number_theory::{discrete_log, mod_pow};
 
-fn find_loop_size(final_val: usize) -> usize {
-    let mut val = 1;
-    const SUBJECT: usize = 7;
+const P: u64 = 20201227;
+const SUBJECT: u64 = 7;
 
-    for loop_size in 1..P {
-        val *= SUBJECT;
-        val = val.rem_euclid(P);
-        if val == final_val {
-            return loop_size;
-        }
-    }
-
-    unreachable!()
+fn find_loop_size(final_val: u64) -> Option<u64> {
+    discrete_log(SUBJECT, final_val, P)
 }
 
-fn do_loop(subject: usize, loop_size: usize) -> usize {
-    let mut val = 1;
-    for _ in 0..loop_size {
-        val *= subject;
-        val = val.rem_euclid(P);
-    }
-    val
+fn do_loop(subject: u64, loop_size: u64) -> u64 {
+    mod_pow(subject, loop_size, P)
 }
 
 fn main() {
@@ -35,7 +22,7 @@ fn main() {
         .expect("Provide the first public key (a number)")
         .parse()
         .expect("Failed to parse second number");
-    let n = find_loop_size(first_key);
+    let n = find_loop_size(first_key).expect("no loop size gives the first key");
     let encription_key = do_loop(second_key, n);
 
     println!("Encription key is {}", encription_key);